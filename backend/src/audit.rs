@@ -0,0 +1,98 @@
+//! Audit trail of administrative and cron-initiated actions.
+//! Recording is best-effort: a failed insert is logged but never fails the action itself.
+
+use crate::entities::{audit_log, contract};
+use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
+use serde::Serialize;
+
+pub const CRON_EXPIRED_CHECKER: &str = "cron:expired_checker";
+pub const CRON_FEE_WITHDRAWAL: &str = "cron:fee_withdrawal";
+pub const CRON_RESOLVER: &str = "cron:resolver";
+
+pub struct AuditEntry {
+    pub actor: String,
+    pub action: &'static str,
+    pub contract_id: Option<i32>,
+    pub market_address: Option<String>,
+    pub payload: Option<String>,
+    pub tx_digest: Option<String>,
+    pub error: Option<String>,
+}
+
+impl AuditEntry {
+    pub fn new(actor: impl ToString, action: &'static str) -> Self {
+        AuditEntry {
+            actor: actor.to_string(),
+            action,
+            contract_id: None,
+            market_address: None,
+            payload: None,
+            tx_digest: None,
+            error: None,
+        }
+    }
+
+    pub fn contract(mut self, contract_id: Option<i32>) -> Self {
+        self.contract_id = contract_id;
+        self
+    }
+
+    pub fn market(mut self, address: impl ToString) -> Self {
+        self.market_address = Some(address.to_string());
+        self
+    }
+
+    pub fn payload<T: Serialize>(mut self, payload: &T) -> Self {
+        self.payload = serde_json::to_string(payload).ok();
+        self
+    }
+
+    pub fn digest(mut self, digest: Option<String>) -> Self {
+        self.tx_digest = digest;
+        self
+    }
+
+    /// A present error marks the entry as a failure
+    pub fn error(mut self, error: Option<String>) -> Self {
+        self.error = error;
+        self
+    }
+}
+
+/// Look up the registered contract id for an on-chain market address
+pub async fn contract_id_for(db: &DatabaseConnection, market_address: &str) -> Option<i32> {
+    contract::Entity::find()
+        .filter(contract::Column::Address.eq(market_address))
+        .one(db)
+        .await
+        .ok()
+        .flatten()
+        .map(|c| c.id)
+}
+
+pub async fn record(db: &DatabaseConnection, entry: AuditEntry) {
+    let outcome = if entry.error.is_some() {
+        "failure"
+    } else {
+        "success"
+    };
+
+    let result = audit_log::ActiveModel {
+        created_at: Set(chrono::Utc::now().to_rfc3339()),
+        actor: Set(entry.actor),
+        action: Set(entry.action.to_string()),
+        contract_id: Set(entry.contract_id),
+        market_address: Set(entry.market_address),
+        payload: Set(entry.payload),
+        tx_digest: Set(entry.tx_digest),
+        outcome: Set(outcome.to_string()),
+        error: Set(entry.error),
+        ..Default::default()
+    }
+    .insert(db)
+    .await;
+
+    if let Err(e) = result {
+        eprintln!("Audit: Failed to record {}: {}", entry.action, e);
+    }
+}
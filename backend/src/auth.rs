@@ -0,0 +1,278 @@
+//! Sign-In-with-Sui style authentication.
+//! A wallet requests a challenge, signs it as a personal message, and exchanges
+//! the signature for a bearer session token. Wallet routes require the session
+//! to match the wallet they act on; admin routes additionally require the
+//! session address to hold a sufficient role in the `accounts` table
+//! (bootstrapped from `ADMIN_ADDRESSES` as super admins).
+
+use crate::entities::account::{self, Role};
+use crate::entities::{auth_nonce, session};
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderMap, StatusCode},
+    middleware::Next,
+    response::Response,
+};
+use rand::Rng;
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, Condition, DatabaseConnection, EntityTrait,
+    QueryFilter, Set,
+};
+use shared_crypto::intent::{Intent, IntentMessage, PersonalMessage};
+use std::str::FromStr;
+use sui_sdk::types::{
+    base_types::SuiAddress,
+    crypto::{Signature, SuiSignature, ToFromBytes},
+};
+
+const CHALLENGE_TTL_SECS: i64 = 5 * 60;
+const DEFAULT_SESSION_TTL_SECS: i64 = 24 * 60 * 60;
+
+/// Authenticated caller, inserted into request extensions by the auth middleware
+#[derive(Clone, Debug)]
+pub struct AuthSession {
+    pub address: SuiAddress,
+    pub role: Option<Role>,
+}
+
+fn random_hex(len: usize) -> String {
+    let mut rng = rand::thread_rng();
+    (0..len)
+        .map(|_| format!("{:02x}", rng.gen::<u8>()))
+        .collect()
+}
+
+fn is_expired(expires_at: &str) -> bool {
+    chrono::DateTime::parse_from_rfc3339(expires_at)
+        .map(|t| t < chrono::Utc::now())
+        .unwrap_or(true)
+}
+
+pub fn parse_address(address: &str) -> Result<SuiAddress, (StatusCode, String)> {
+    SuiAddress::from_str(address.trim())
+        .map_err(|e| (StatusCode::BAD_REQUEST, format!("Invalid address: {}", e)))
+}
+
+/// Bootstrap super admins (`ADMIN_ADDRESSES`, comma-separated), seeded into an empty `accounts` table at startup
+pub fn admin_addresses() -> Vec<SuiAddress> {
+    std::env::var("ADMIN_ADDRESSES")
+        .unwrap_or_default()
+        .split(',')
+        .filter_map(|a| SuiAddress::from_str(a.trim()).ok())
+        .collect()
+}
+
+pub async fn account_role(
+    db: &DatabaseConnection,
+    address: &SuiAddress,
+) -> Result<Option<Role>, (StatusCode, String)> {
+    let account = account::Entity::find()
+        .filter(account::Column::Address.eq(address.to_string()))
+        .one(db)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(account.map(|a| a.role))
+}
+
+/// Verify a base64 serialized signature (flag || sig || pubkey) over a personal message
+pub fn verify_personal_message(
+    address: SuiAddress,
+    message: &str,
+    signature_b64: &str,
+) -> Result<(), String> {
+    use base64::Engine;
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(signature_b64.trim())
+        .map_err(|e| format!("Signature is not valid base64: {}", e))?;
+    let signature =
+        Signature::from_bytes(&bytes).map_err(|e| format!("Unsupported signature: {}", e))?;
+
+    let intent_msg = IntentMessage::new(
+        Intent::personal_message(),
+        PersonalMessage {
+            message: message.as_bytes().to_vec(),
+        },
+    );
+
+    signature
+        .verify_secure(&intent_msg, address, signature.scheme())
+        .map_err(|e| format!("Signature verification failed: {}", e))
+}
+
+/// Create a one-time challenge message for `address` to sign
+pub async fn create_challenge(
+    db: &DatabaseConnection,
+    address: SuiAddress,
+) -> Result<auth_nonce::Model, (StatusCode, String)> {
+    let nonce = random_hex(16);
+    let issued_at = chrono::Utc::now();
+    let expires_at = issued_at + chrono::Duration::seconds(CHALLENGE_TTL_SECS);
+
+    let message = format!(
+        "play-sui wants you to sign in with your Sui account:\n{}\n\nNonce: {}\nIssued At: {}\nExpiration Time: {}",
+        address,
+        nonce,
+        issued_at.to_rfc3339(),
+        expires_at.to_rfc3339()
+    );
+
+    auth_nonce::ActiveModel {
+        nonce: Set(nonce),
+        address: Set(address.to_string()),
+        message: Set(message),
+        expires_at: Set(expires_at.to_rfc3339()),
+        used: Set(false),
+        ..Default::default()
+    }
+    .insert(db)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+/// Check a signed challenge and burn its nonce so it cannot be replayed
+pub async fn consume_challenge(
+    db: &DatabaseConnection,
+    address: SuiAddress,
+    nonce: &str,
+    signature_b64: &str,
+) -> Result<(), (StatusCode, String)> {
+    let challenge = auth_nonce::Entity::find()
+        .filter(auth_nonce::Column::Nonce.eq(nonce))
+        .one(db)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::UNAUTHORIZED, "Unknown nonce".to_string()))?;
+
+    if challenge.used {
+        return Err((StatusCode::UNAUTHORIZED, "Nonce already used".to_string()));
+    }
+    if is_expired(&challenge.expires_at) {
+        return Err((StatusCode::UNAUTHORIZED, "Challenge expired".to_string()));
+    }
+    if challenge.address != address.to_string() {
+        return Err((
+            StatusCode::UNAUTHORIZED,
+            "Challenge was issued for a different address".to_string(),
+        ));
+    }
+
+    verify_personal_message(address, &challenge.message, signature_b64)
+        .map_err(|e| (StatusCode::UNAUTHORIZED, e))?;
+
+    // Only one of two concurrent logins with the same signature gets the nonce
+    let burned = auth_nonce::Entity::update_many()
+        .col_expr(auth_nonce::Column::Used, Expr::value(true))
+        .filter(auth_nonce::Column::Nonce.eq(nonce))
+        .filter(auth_nonce::Column::Used.eq(false))
+        .exec(db)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if burned.rows_affected == 0 {
+        return Err((StatusCode::UNAUTHORIZED, "Nonce already used".to_string()));
+    }
+
+    Ok(())
+}
+
+/// Delete used or expired challenges and expired sessions
+pub async fn prune_expired(db: &DatabaseConnection) -> Result<(), sea_orm::DbErr> {
+    let now = chrono::Utc::now().to_rfc3339();
+    auth_nonce::Entity::delete_many()
+        .filter(
+            Condition::any()
+                .add(auth_nonce::Column::Used.eq(true))
+                .add(auth_nonce::Column::ExpiresAt.lt(&now)),
+        )
+        .exec(db)
+        .await?;
+    session::Entity::delete_many()
+        .filter(session::Column::ExpiresAt.lt(&now))
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+pub async fn create_session(
+    db: &DatabaseConnection,
+    address: SuiAddress,
+) -> Result<session::Model, (StatusCode, String)> {
+    let ttl = std::env::var("SESSION_TTL_SECS")
+        .ok()
+        .and_then(|s| s.parse::<i64>().ok())
+        .unwrap_or(DEFAULT_SESSION_TTL_SECS);
+    let now = chrono::Utc::now();
+
+    session::ActiveModel {
+        token: Set(random_hex(32)),
+        address: Set(address.to_string()),
+        created_at: Set(now.to_rfc3339()),
+        expires_at: Set((now + chrono::Duration::seconds(ttl)).to_rfc3339()),
+        ..Default::default()
+    }
+    .insert(db)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+/// Resolve the `Authorization: Bearer <token>` header to a live session
+pub async fn session_from_headers(
+    db: &DatabaseConnection,
+    headers: &HeaderMap,
+) -> Result<AuthSession, (StatusCode, String)> {
+    let token = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .ok_or((StatusCode::UNAUTHORIZED, "Missing bearer token".to_string()))?;
+
+    let session = session::Entity::find()
+        .filter(session::Column::Token.eq(token.trim()))
+        .one(db)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::UNAUTHORIZED, "Invalid session".to_string()))?;
+
+    if is_expired(&session.expires_at) {
+        return Err((StatusCode::UNAUTHORIZED, "Session expired".to_string()));
+    }
+
+    let address = SuiAddress::from_str(&session.address)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let role = account_role(db, &address).await?;
+
+    Ok(AuthSession { address, role })
+}
+
+/// Middleware for endpoints acting on behalf of a wallet (any signed-in address)
+pub async fn require_session(
+    State(db): State<DatabaseConnection>,
+    mut req: Request,
+    next: Next,
+) -> Result<Response, (StatusCode, String)> {
+    let session = session_from_headers(&db, req.headers()).await?;
+    req.extensions_mut().insert(session);
+    Ok(next.run(req).await)
+}
+
+/// Middleware for admin endpoints; the state carries the role the route group requires
+pub async fn require_role(
+    State((db, required)): State<(DatabaseConnection, Role)>,
+    mut req: Request,
+    next: Next,
+) -> Result<Response, (StatusCode, String)> {
+    let session = session_from_headers(&db, req.headers()).await?;
+
+    match session.role {
+        Some(role) if role.allows(required) => {}
+        _ => {
+            return Err((
+                StatusCode::FORBIDDEN,
+                format!("{} lacks the {:?} role", session.address, required),
+            ))
+        }
+    }
+
+    req.extensions_mut().insert(session);
+    Ok(next.run(req).await)
+}
@@ -0,0 +1,334 @@
+//! Typed reads of on-chain `polymarket::market::Market` objects, events and abort codes.
+
+use axum::http::StatusCode;
+use serde::Deserialize;
+use std::str::FromStr;
+use sui_sdk::{
+    rpc_types::{SuiObjectData, SuiObjectDataOptions, SuiParsedData, SuiPastObjectResponse},
+    types::base_types::{ObjectID, SequenceNumber, SuiAddress},
+    SuiClient,
+};
+
+pub const DEFAULT_SUI_NETWORK: &str = "https://fullnode.testnet.sui.io:443";
+
+/// Fullnode URL from `SUI_NETWORK`, defaulting to testnet
+pub fn sui_network() -> String {
+    std::env::var("SUI_NETWORK").unwrap_or_else(|_| DEFAULT_SUI_NETWORK.to_string())
+}
+
+/// Configured `PACKAGE_ID`
+pub fn package_id() -> Result<ObjectID, String> {
+    let id = std::env::var("PACKAGE_ID").map_err(|_| "PACKAGE_ID not set".to_string())?;
+    ObjectID::from_str(&id).map_err(|e| format!("Invalid PACKAGE_ID: {}", e))
+}
+
+#[derive(Debug)]
+pub enum MarketLookupError {
+    /// The fullnode could not be reached or returned an error
+    Rpc(String),
+    /// The object does not exist or is not one of our markets
+    Invalid(String),
+}
+
+impl std::fmt::Display for MarketLookupError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MarketLookupError::Rpc(e) | MarketLookupError::Invalid(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+/// Snapshot of a `Market` object's fields
+#[derive(Clone, Debug)]
+pub struct ChainMarket {
+    pub id: ObjectID,
+    pub options_count: u8,
+    pub total_stakes: Vec<u64>,
+    pub resolved: bool,
+    pub cancelled: bool,
+    pub winner: Option<u8>,
+    pub end_time_ms: Option<u64>,
+    pub oracle: SuiAddress,
+    pub platform_fee_bps: u16,
+    pub platform_balance: u64,
+    pub platform_admin: SuiAddress,
+}
+
+/// `market::MarketCreated` event payload
+#[derive(Debug, Deserialize)]
+pub struct MarketCreatedEvent {
+    pub id: String,
+    pub oracle: String,
+}
+
+/// Abort codes of the `market` module
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MarketAbort {
+    AlreadyResolved,    // EMarketAlreadyResolved = 0, also raised for cancelled markets
+    NotResolved,        // EMarketNotResolved = 1
+    InvalidOutcome,     // EInvalidOutcome = 2
+    NotAuthorized,      // ENotAuthorized = 3
+    InvalidPlatformFee, // EInvalidPlatformFee = 5
+    NoFees,             // ENoFees = 6
+    NotExpired,         // EMarketNotExpired = 7
+}
+
+impl MarketAbort {
+    pub fn from_code(code: u64) -> Option<MarketAbort> {
+        match code {
+            0 => Some(MarketAbort::AlreadyResolved),
+            1 => Some(MarketAbort::NotResolved),
+            2 => Some(MarketAbort::InvalidOutcome),
+            3 => Some(MarketAbort::NotAuthorized),
+            5 => Some(MarketAbort::InvalidPlatformFee),
+            6 => Some(MarketAbort::NoFees),
+            7 => Some(MarketAbort::NotExpired),
+            _ => None,
+        }
+    }
+
+    pub fn code(self) -> u64 {
+        match self {
+            MarketAbort::AlreadyResolved => 0,
+            MarketAbort::NotResolved => 1,
+            MarketAbort::InvalidOutcome => 2,
+            MarketAbort::NotAuthorized => 3,
+            MarketAbort::InvalidPlatformFee => 5,
+            MarketAbort::NoFees => 6,
+            MarketAbort::NotExpired => 7,
+        }
+    }
+
+    /// Machine-readable error code for API responses
+    pub fn name(self) -> &'static str {
+        match self {
+            MarketAbort::AlreadyResolved => "market_already_resolved",
+            MarketAbort::NotResolved => "market_not_resolved",
+            MarketAbort::InvalidOutcome => "invalid_outcome",
+            MarketAbort::NotAuthorized => "not_authorized",
+            MarketAbort::InvalidPlatformFee => "invalid_platform_fee",
+            MarketAbort::NoFees => "no_fees",
+            MarketAbort::NotExpired => "market_not_expired",
+        }
+    }
+
+    pub fn message(self) -> &'static str {
+        match self {
+            MarketAbort::AlreadyResolved => "Market is already resolved or cancelled",
+            MarketAbort::NotResolved => "Market is not resolved yet",
+            MarketAbort::InvalidOutcome => "Outcome index is out of range for this market",
+            MarketAbort::NotAuthorized => "Sender is not the market's oracle or platform admin",
+            MarketAbort::InvalidPlatformFee => "Platform fee is above the allowed maximum",
+            MarketAbort::NoFees => "Market holds no platform fees",
+            MarketAbort::NotExpired => "Market has not expired yet",
+        }
+    }
+
+    pub fn status_code(self) -> StatusCode {
+        match self {
+            MarketAbort::AlreadyResolved
+            | MarketAbort::NotResolved
+            | MarketAbort::NoFees
+            | MarketAbort::NotExpired => StatusCode::CONFLICT,
+            MarketAbort::InvalidOutcome | MarketAbort::InvalidPlatformFee => {
+                StatusCode::BAD_REQUEST
+            }
+            MarketAbort::NotAuthorized => StatusCode::FORBIDDEN,
+        }
+    }
+
+    /// Find a `market` module abort in an execution error such as
+    /// `MoveAbort(MoveLocation { module: ModuleId { address: .., name: Identifier("market") }, .. }, 0) in command 0`
+    pub fn parse(error: &str) -> Option<MarketAbort> {
+        let abort = &error[error.find("MoveAbort(")?..];
+        if !abort.contains("name: Identifier(\"market\")") {
+            return None;
+        }
+        // The code follows the location struct: `.. }, <code>)`
+        abort.match_indices("}, ").find_map(|(i, sep)| {
+            let rest = &abort[i + sep.len()..];
+            let digits = rest.find(|c: char| !c.is_ascii_digit())?;
+            if digits == 0 || !rest[digits..].starts_with(')') {
+                return None;
+            }
+            rest[..digits].parse().ok().and_then(MarketAbort::from_code)
+        })
+    }
+}
+
+/// Move integers arrive as JSON numbers (u8/u16/u32) or strings (u64+);
+/// `Balance` may be wrapped as `{ "fields": { "value": .. } }`
+fn json_u64(v: &serde_json::Value) -> Option<u64> {
+    match v {
+        serde_json::Value::Number(n) => n.as_u64(),
+        serde_json::Value::String(s) => s.parse().ok(),
+        serde_json::Value::Object(_) => v
+            .get("fields")
+            .and_then(|f| f.get("value"))
+            .or_else(|| v.get("value"))
+            .and_then(json_u64),
+        _ => None,
+    }
+}
+
+fn json_address(v: Option<&serde_json::Value>) -> Option<SuiAddress> {
+    v.and_then(|v| v.as_str())
+        .and_then(|s| SuiAddress::from_str(s).ok())
+}
+
+/// Fetch `market_id` and check it is a `market::Market` from `package_id`
+pub async fn fetch_market(
+    client: &SuiClient,
+    package_id: ObjectID,
+    market_id: ObjectID,
+) -> Result<ChainMarket, MarketLookupError> {
+    let response = client
+        .read_api()
+        .get_object_with_options(
+            market_id,
+            SuiObjectDataOptions::new().with_type().with_content(),
+        )
+        .await
+        .map_err(|e| MarketLookupError::Rpc(format!("Failed to read object: {}", e)))?;
+
+    let data = response
+        .data
+        .ok_or_else(|| MarketLookupError::Invalid(format!("Object {} not found", market_id)))?;
+    parse_market(package_id, market_id, data)
+}
+
+/// `market_id` as it was at `version`, e.g. just before a transaction that modified it
+pub async fn fetch_market_at(
+    client: &SuiClient,
+    package_id: ObjectID,
+    market_id: ObjectID,
+    version: SequenceNumber,
+) -> Result<ChainMarket, MarketLookupError> {
+    let response = client
+        .read_api()
+        .try_get_parsed_past_object(
+            market_id,
+            version,
+            SuiObjectDataOptions::new().with_type().with_content(),
+        )
+        .await
+        .map_err(|e| MarketLookupError::Rpc(format!("Failed to read object: {}", e)))?;
+
+    match response {
+        SuiPastObjectResponse::VersionFound(data) => parse_market(package_id, market_id, data),
+        _ => Err(MarketLookupError::Invalid(format!(
+            "Object {} has no version {}",
+            market_id, version
+        ))),
+    }
+}
+
+fn parse_market(
+    package_id: ObjectID,
+    market_id: ObjectID,
+    data: SuiObjectData,
+) -> Result<ChainMarket, MarketLookupError> {
+    let parsed = match data.content {
+        Some(SuiParsedData::MoveObject(obj)) => obj,
+        _ => {
+            return Err(MarketLookupError::Invalid(format!(
+                "Object {} is not a Move object",
+                market_id
+            )))
+        }
+    };
+
+    let type_ = &parsed.type_;
+    if ObjectID::from(type_.address) != package_id
+        || type_.module.as_str() != "market"
+        || type_.name.as_str() != "Market"
+    {
+        return Err(MarketLookupError::Invalid(format!(
+            "Object {} has type {}, expected {}::market::Market",
+            market_id, type_, package_id
+        )));
+    }
+
+    let fields = serde_json::to_value(&parsed.fields).unwrap_or_default();
+    let invalid = |field: &str| {
+        MarketLookupError::Invalid(format!("Market {} has no readable `{}`", market_id, field))
+    };
+
+    let total_stakes = fields
+        .get("total_stakes")
+        .and_then(|v| v.as_array())
+        .map(|a| a.iter().map(|v| json_u64(v).unwrap_or(0)).collect())
+        .unwrap_or_default();
+
+    Ok(ChainMarket {
+        id: market_id,
+        options_count: fields
+            .get("options_count")
+            .and_then(json_u64)
+            .ok_or_else(|| invalid("options_count"))? as u8,
+        total_stakes,
+        resolved: fields
+            .get("resolved")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
+        cancelled: fields
+            .get("cancelled")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
+        winner: fields.get("winner").and_then(json_u64).map(|w| w as u8),
+        end_time_ms: fields.get("end_time_ms").and_then(json_u64),
+        oracle: json_address(fields.get("oracle")).ok_or_else(|| invalid("oracle"))?,
+        platform_fee_bps: fields
+            .get("platform_fee_bps")
+            .and_then(json_u64)
+            .unwrap_or(0) as u16,
+        platform_balance: fields
+            .get("platform_balance")
+            .and_then(json_u64)
+            .unwrap_or(0),
+        platform_admin: json_address(fields.get("platform_admin"))
+            .ok_or_else(|| invalid("platform_admin"))?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn abort_error(module: &str, code: u64) -> String {
+        format!(
+            "MoveAbort(MoveLocation {{ module: ModuleId {{ address: 00000000000000000000000000000000000000000000000000000000000000aa, name: Identifier(\"{}\") }}, function: 3, instruction: 18, function_name: Some(\"resolve_market\") }}, {}) in command 0",
+            module, code
+        )
+    }
+
+    #[test]
+    fn parses_every_market_abort() {
+        for code in [0, 1, 2, 3, 5, 6, 7] {
+            let abort = MarketAbort::parse(&abort_error("market", code)).unwrap();
+            assert_eq!(abort.code(), code);
+            assert_eq!(MarketAbort::from_code(code), Some(abort));
+        }
+    }
+
+    #[test]
+    fn ignores_unknown_codes() {
+        assert_eq!(MarketAbort::parse(&abort_error("market", 4)), None);
+        assert_eq!(MarketAbort::parse(&abort_error("market", 70)), None);
+    }
+
+    #[test]
+    fn ignores_aborts_from_other_modules() {
+        assert_eq!(MarketAbort::parse(&abort_error("coin", 0)), None);
+    }
+
+    #[test]
+    fn ignores_other_errors() {
+        assert_eq!(MarketAbort::parse(""), None);
+        assert_eq!(MarketAbort::parse("InsufficientGas in command 0"), None);
+        assert_eq!(
+            MarketAbort::parse("MoveAbort(MoveLocation { module: ModuleId { address: aa, name: Identifier(\"market\") }"),
+            None
+        );
+    }
+}
@@ -0,0 +1,98 @@
+//! CORS policy configured at startup.
+//!
+//! - `CORS_ALLOWED_ORIGINS`: comma-separated origins, or `*`
+//! - `CORS_ALLOWED_METHODS`: comma-separated methods
+//! - `CORS_ALLOWED_HEADERS`: comma-separated request headers
+//! - `CORS_ALLOW_CREDENTIALS`: `true` to allow cookies/credentials
+//!
+//! Debug builds default to any origin (Vite dev server on another port).
+//! Release builds default to no cross-origin access: the embedded frontend is
+//! same-origin, so only explicitly configured origins can call the API.
+
+use crate::idempotency;
+use crate::tx;
+use axum::http::{header, HeaderName, HeaderValue, Method};
+use tower_http::cors::{AllowOrigin, CorsLayer};
+
+fn env_list(var: &str) -> Option<Vec<String>> {
+    std::env::var(var).ok().map(|v| {
+        v.split(',')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect()
+    })
+}
+
+pub fn cors_layer() -> CorsLayer {
+    let origins = env_list("CORS_ALLOWED_ORIGINS");
+    let wildcard = match &origins {
+        Some(list) => list.iter().any(|o| o == "*"),
+        None => cfg!(debug_assertions),
+    };
+
+    let allow_origin = if wildcard {
+        AllowOrigin::any()
+    } else {
+        let parsed: Vec<HeaderValue> = origins
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|o| match HeaderValue::from_str(o) {
+                Ok(v) => Some(v),
+                Err(_) => {
+                    eprintln!("CORS: ignoring invalid origin {:?}", o);
+                    None
+                }
+            })
+            .collect();
+        AllowOrigin::list(parsed)
+    };
+
+    let methods: Vec<Method> = env_list("CORS_ALLOWED_METHODS")
+        .map(|list| {
+            list.iter()
+                .filter_map(|m| m.to_uppercase().parse().ok())
+                .collect()
+        })
+        .unwrap_or_else(|| vec![Method::GET, Method::POST, Method::DELETE, Method::OPTIONS]);
+
+    let headers: Vec<HeaderName> = env_list("CORS_ALLOWED_HEADERS")
+        .map(|list| list.iter().filter_map(|h| h.parse().ok()).collect())
+        .unwrap_or_else(|| {
+            vec![
+                header::CONTENT_TYPE,
+                header::AUTHORIZATION,
+                HeaderName::from_static(idempotency::IDEMPOTENCY_KEY_HEADER),
+            ]
+        });
+
+    let mut credentials = std::env::var("CORS_ALLOW_CREDENTIALS")
+        .map(|v| v == "true")
+        .unwrap_or(false);
+    if credentials && wildcard {
+        // The CORS spec forbids credentials with a wildcard origin
+        eprintln!("CORS: CORS_ALLOW_CREDENTIALS ignored because origins are `*`");
+        credentials = false;
+    }
+
+    println!(
+        "CORS: origins={}, methods={:?}, credentials={}",
+        if wildcard {
+            "*".to_string()
+        } else {
+            std::env::var("CORS_ALLOWED_ORIGINS").unwrap_or_else(|_| "(none)".to_string())
+        },
+        methods,
+        credentials
+    );
+
+    CorsLayer::new()
+        .allow_origin(allow_origin)
+        .allow_methods(methods)
+        .allow_headers(headers)
+        .allow_credentials(credentials)
+        .expose_headers([
+            header::RETRY_AFTER,
+            HeaderName::from_static(tx::GAS_BUDGET_HEADER),
+            HeaderName::from_static(tx::GAS_USED_HEADER),
+        ])
+}
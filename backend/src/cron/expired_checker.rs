@@ -1,22 +1,41 @@
 //! Expired Markets Checker Cron Job
 //! Periodically checks for markets past their end_date that haven't been resolved
-//! and automatically cancels them (triggering refunds)
+//! and automatically cancels them (triggering refunds); also prunes spent
+//! login challenges and expired sessions
 
+use crate::audit;
+use crate::auth;
 use crate::entities::contract;
-use crate::handlers::cancel;
+use crate::handlers::{cancel, resolution};
+use crate::jobs::{self, JobRun};
+use crate::resolver;
+use crate::validation;
 use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use std::collections::HashMap;
 use std::time::Duration;
-use tokio::time;
+use tokio::time::{self, Instant};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+/// Longest wait before retrying a market whose cancellation keeps failing
+const MAX_BACKOFF: Duration = Duration::from_secs(3600);
 
 pub async fn run_expired_checker(db: DatabaseConnection) {
     println!("Starting Expired Markets Checker Task...");
 
     // Check every 30 seconds
-    let mut interval = time::interval(Duration::from_secs(30));
+    let mut interval = time::interval(CHECK_INTERVAL);
+    // Markets whose cancellation failed, by contract id: failures so far and
+    // when to try again. Each attempt is audited, so retries back off.
+    let mut backoff: HashMap<i32, (u32, Instant)> = HashMap::new();
 
     loop {
         interval.tick().await;
 
+        // Spent challenges and lapsed sessions are never read again
+        if let Err(e) = auth::prune_expired(&db).await {
+            eprintln!("ExpiredChecker: Failed to prune auth records: {}", e);
+        }
+
         // Find expired, unresolved markets
         let now = chrono::Utc::now();
 
@@ -41,39 +60,88 @@ pub async fn run_expired_checker(db: DatabaseConnection) {
             };
 
             // Parse end_date
-            let end_date = match chrono::DateTime::parse_from_rfc3339(end_date_str) {
-                Ok(d) => d,
-                Err(_) => {
-                    // Try parsing as date only
-                    match chrono::NaiveDate::parse_from_str(end_date_str, "%Y-%m-%d") {
-                        Ok(d) => d.and_hms_opt(23, 59, 59).unwrap().and_utc().fixed_offset(),
-                        Err(_) => continue,
-                    }
-                }
+            let end_date = match validation::parse_end_date(end_date_str) {
+                Some(d) => d,
+                None => continue,
             };
 
+            if backoff
+                .get(&contract_model.id)
+                .is_some_and(|(_, retry_at)| Instant::now() < *retry_at)
+            {
+                continue;
+            }
+
             // Check if expired
             if now > end_date {
+                // An earlier cancellation may still be awaiting its outcome,
+                // the oracle may be signing an offline resolution, or an
+                // automated resolver is due to settle the market
+                if jobs::open_job_for(&db, &contract_model.address)
+                    .await
+                    .is_some()
+                    || resolution::awaiting_for(&db, &contract_model.address)
+                        .await
+                        .is_some()
+                {
+                    continue;
+                }
+                match resolver::active_for(&db, &contract_model.address).await {
+                    Ok(None) => {}
+                    Ok(Some(_)) => continue,
+                    // Unknown; cancelling could pre-empt the resolver
+                    Err(e) => {
+                        eprintln!(
+                            "ExpiredChecker: Failed to look up resolver of market {}: {}",
+                            contract_model.id, e
+                        );
+                        continue;
+                    }
+                }
+
                 println!(
                     "ExpiredChecker: Market {} ({}) has expired (end_date: {}), cancelling...",
                     contract_model.id, contract_model.name, end_date_str
                 );
 
-                // Call cancel internal function
-                match cancel::execute_cancel_market(&contract_model.address).await {
-                    Ok(digest) => {
+                // Call cancel internal function; the job records the audit entry
+                let result = cancel::execute_cancel_market(
+                    &db,
+                    audit::CRON_EXPIRED_CHECKER,
+                    &contract_model.address,
+                )
+                .await;
+
+                if result.is_ok() {
+                    backoff.remove(&contract_model.id);
+                }
+                match result {
+                    Ok(JobRun::Confirmed { outcome, .. }) => {
                         println!(
                             "ExpiredChecker: Successfully cancelled market {} (Digest: {})",
-                            contract_model.id, digest
+                            contract_model.id, outcome.digest
                         );
 
                         // Optional: Mark as cancelled in DB immediately?
                         // The indexer will pick it up, but we could update here too.
                     }
+                    Ok(JobRun::Pending(job)) => {
+                        println!(
+                            "ExpiredChecker: Cancellation of market {} pending (job {})",
+                            contract_model.id, job.id
+                        );
+                    }
                     Err(e) => {
+                        let failures = backoff.get(&contract_model.id).map_or(1, |(n, _)| n + 1);
+                        let delay = CHECK_INTERVAL
+                            .saturating_mul(1 << failures.min(7))
+                            .min(MAX_BACKOFF);
+                        backoff.insert(contract_model.id, (failures, Instant::now() + delay));
                         eprintln!(
-                            "ExpiredChecker: Failed to cancel market {}: {}",
-                            contract_model.id, e
+                            "ExpiredChecker: Failed to cancel market {}: {}; retrying in {}s",
+                            contract_model.id,
+                            e,
+                            delay.as_secs()
                         );
                     }
                 }
@@ -0,0 +1,41 @@
+//! Fee Withdrawal Cron Job
+//! Periodically withdraws accumulated platform fees from markets whose
+//! `platform_admin` is the backend signer, one batch per run
+
+use crate::audit;
+use crate::fees::{self, FeeConfig};
+use crate::jobs::JobRun;
+use tokio::time;
+
+pub async fn run_fee_withdrawal(db: sea_orm::DatabaseConnection) {
+    let config = FeeConfig::from_env();
+    if config.interval.is_zero() {
+        println!("Fees: disabled (FEE_WITHDRAW_INTERVAL_SECS=0)");
+        return;
+    }
+    println!(
+        "Starting Fee Withdrawal Task (every {}s, minimum {} MIST)...",
+        config.interval.as_secs(),
+        config.min_amount
+    );
+
+    let mut interval = time::interval(config.interval);
+
+    loop {
+        interval.tick().await;
+
+        match fees::withdraw(&db, audit::CRON_FEE_WITHDRAWAL, None, &config).await {
+            Ok(None) => {}
+            Ok(Some((markets, JobRun::Confirmed { outcome, .. }))) => println!(
+                "Fees: Withdrew {} MIST from {} markets (Digest: {})",
+                markets.iter().map(|m| m.amount).sum::<u64>(),
+                markets.len(),
+                outcome.digest
+            ),
+            Ok(Some((_, JobRun::Pending(job)))) => {
+                println!("Fees: Withdrawal pending (job {})", job.id)
+            }
+            Err(e) => eprintln!("Fees: Failed to withdraw platform fees: {}", e),
+        }
+    }
+}
@@ -0,0 +1,38 @@
+//! Gas Pool Cron Job
+//! Keeps enough gas coins for concurrent admin transactions and merges dust
+
+use crate::gas::{self, GasPoolConfig};
+use crate::tx::Executor;
+use tokio::time;
+
+pub async fn run_gas_pool() {
+    let config = GasPoolConfig::from_env();
+    if config.pool_size == 0 {
+        println!("GasPool: disabled (GAS_POOL_SIZE=0)");
+        return;
+    }
+    println!(
+        "Starting Gas Pool Task (size {}, {} MIST per coin)...",
+        config.pool_size, config.coin_balance
+    );
+
+    let mut interval = time::interval(config.interval);
+
+    loop {
+        interval.tick().await;
+
+        let executor = match Executor::from_env().await {
+            Ok(executor) => executor,
+            Err(e) => {
+                eprintln!("GasPool: {}", e);
+                continue;
+            }
+        };
+
+        match gas::maintain_pool(&executor, &config).await {
+            Ok(Some(digest)) => println!("GasPool: Rebalanced gas coins (Digest: {})", digest),
+            Ok(None) => {}
+            Err(e) => eprintln!("GasPool: Failed to rebalance gas coins: {}", e),
+        }
+    }
+}
@@ -1,16 +1,23 @@
 use crate::entities::{contract, market_history};
+use crate::handlers::stream::{self, EventBus, StreamEvent};
 use sea_orm::{
     ActiveModelTrait, ActiveValue, ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait,
     QueryFilter,
 };
+use std::collections::HashMap;
 use std::str::FromStr;
 use std::time::Duration;
 use sui_sdk::rpc_types::{EventFilter, SuiObjectDataOptions};
 use sui_sdk::types::base_types::ObjectID;
+use sui_sdk::types::event::EventID;
 use sui_sdk::SuiClientBuilder;
 use tokio::time;
 
-pub async fn run_indexer(db: DatabaseConnection, mut rx: tokio::sync::mpsc::Receiver<()>) {
+pub async fn run_indexer(
+    db: DatabaseConnection,
+    mut rx: tokio::sync::mpsc::Receiver<()>,
+    bus: EventBus,
+) {
     println!("Starting Indexer Task...");
 
     // Load PACKAGE_ID from environment variable (set by `just dev-run`)
@@ -36,6 +43,9 @@ pub async fn run_indexer(db: DatabaseConnection, mut rx: tokio::sync::mpsc::Rece
         }
     };
 
+    // Start streaming live events from the current chain tip (history is backfilled separately)
+    let mut event_cursor = latest_event_cursor(&sui_client, &package_id_str).await;
+
     let mut interval = time::interval(Duration::from_secs(2));
 
     loop {
@@ -56,6 +66,16 @@ pub async fn run_indexer(db: DatabaseConnection, mut rx: tokio::sync::mpsc::Rece
             }
         };
 
+        // Push bets / resolutions / cancellations to stream subscribers
+        poll_market_events(
+            &sui_client,
+            &contracts,
+            &package_id_str,
+            &mut event_cursor,
+            &bus,
+        )
+        .await;
+
         for contract_model in contracts {
             // Skip if no on-chain ID (shouldn't happen for active markets)
             if contract_model.address.is_empty() {
@@ -186,7 +206,7 @@ pub async fn run_indexer(db: DatabaseConnection, mut rx: tokio::sync::mpsc::Rece
 
                                 let new_history = market_history::ActiveModel {
                                     contract_id: ActiveValue::Set(contract_model.id),
-                                    timestamp: ActiveValue::Set(now),
+                                    timestamp: ActiveValue::Set(now.clone()),
                                     option_prices: ActiveValue::Set(json_prices.clone()),
                                     total_volume: ActiveValue::Set(volume_sui),
                                     ..Default::default()
@@ -201,6 +221,15 @@ pub async fn run_indexer(db: DatabaseConnection, mut rx: tokio::sync::mpsc::Rece
                                         "Indexer: Updated market {} prices",
                                         contract_model.id
                                     );
+                                    stream::publish(
+                                        &bus,
+                                        StreamEvent::History {
+                                            contract_id: contract_model.id,
+                                            timestamp: now,
+                                            option_prices: prices.clone(),
+                                            total_volume: volume_sui,
+                                        },
+                                    );
                                 }
                             }
 
@@ -245,6 +274,130 @@ pub async fn run_indexer(db: DatabaseConnection, mut rx: tokio::sync::mpsc::Rece
     }
 }
 
+fn market_module_filter(package_id_str: &str) -> EventFilter {
+    EventFilter::MoveModule {
+        package: ObjectID::from_str(package_id_str).unwrap_or(ObjectID::ZERO),
+        module: "market".parse().unwrap(),
+    }
+}
+
+/// Cursor of the newest `market` module event, so live polling skips past events.
+/// Retries until the fullnode answers: starting from `None` would replay every
+/// historical event to stream subscribers as if it were live.
+async fn latest_event_cursor(
+    sui_client: &sui_sdk::SuiClient,
+    package_id_str: &str,
+) -> Option<EventID> {
+    loop {
+        match sui_client
+            .event_api()
+            .query_events(market_module_filter(package_id_str), None, Some(1), true)
+            .await
+        {
+            // No events yet is a valid starting point
+            Ok(page) => return page.data.first().map(|e| e.id),
+            Err(e) => {
+                eprintln!(
+                    "Indexer: Failed to query latest event, retrying in 5s: {}",
+                    e
+                );
+                time::sleep(Duration::from_secs(5)).await;
+            }
+        }
+    }
+}
+
+/// Fetch `market` module events newer than `cursor` and broadcast them
+async fn poll_market_events(
+    sui_client: &sui_sdk::SuiClient,
+    contracts: &[contract::Model],
+    package_id_str: &str,
+    cursor: &mut Option<EventID>,
+    bus: &EventBus,
+) {
+    let by_object: HashMap<ObjectID, i32> = contracts
+        .iter()
+        .filter_map(|c| ObjectID::from_str(&c.address).ok().map(|id| (id, c.id)))
+        .collect();
+
+    loop {
+        let page = match sui_client
+            .event_api()
+            .query_events(market_module_filter(package_id_str), *cursor, None, false)
+            .await
+        {
+            Ok(p) => p,
+            Err(e) => {
+                eprintln!("Indexer: Failed to poll events: {}", e);
+                return;
+            }
+        };
+
+        if let Some(last) = page.data.last() {
+            *cursor = Some(last.id);
+        }
+
+        for event in &page.data {
+            let contract_id = match event
+                .parsed_json
+                .get("market_id")
+                .and_then(|v| v.as_str())
+                .and_then(|s| ObjectID::from_str(s).ok())
+                .and_then(|id| by_object.get(&id))
+            {
+                Some(id) => *id,
+                None => continue, // Not one of our indexed markets
+            };
+            let tx_digest = event.id.tx_digest.to_string();
+            let event_type = event.type_.name.as_str();
+
+            let stream_event = match event_type {
+                "BetPlaced" => StreamEvent::Bet {
+                    contract_id,
+                    better: event
+                        .parsed_json
+                        .get("better")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or_default()
+                        .to_string(),
+                    outcome: event
+                        .parsed_json
+                        .get("outcome")
+                        .and_then(|v| v.as_u64())
+                        .unwrap_or(0) as u8,
+                    amount: event
+                        .parsed_json
+                        .get("amount")
+                        .and_then(|v| v.as_str())
+                        .and_then(|s| s.parse().ok())
+                        .unwrap_or(0),
+                    tx_digest,
+                },
+                "MarketResolved" => StreamEvent::Resolved {
+                    contract_id,
+                    winner: event
+                        .parsed_json
+                        .get("winner")
+                        .and_then(|v| v.as_u64())
+                        .unwrap_or(0) as u8,
+                    tx_digest,
+                },
+                "MarketCancelled" => StreamEvent::Cancelled {
+                    contract_id,
+                    tx_digest,
+                },
+                _ => continue,
+            };
+
+            stream::publish(bus, stream_event);
+        }
+
+        if !page.has_next_page {
+            break;
+        }
+    }
+}
+
 async fn backfill_history_if_needed(
     db: &DatabaseConnection,
     sui_client: &sui_sdk::SuiClient,
@@ -266,10 +419,7 @@ async fn backfill_history_if_needed(
     println!("Indexer: Backfilling history for contract {}", contract.id);
 
     // Query Events via Module
-    let query = EventFilter::MoveModule {
-        package: ObjectID::from_str(package_id_str).unwrap_or(ObjectID::ZERO),
-        module: "market".parse().unwrap(),
-    };
+    let query = market_module_filter(package_id_str);
 
     let mut cursor = None;
 
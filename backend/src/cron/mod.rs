@@ -1,2 +1,6 @@
 pub mod expired_checker;
+pub mod fee_withdrawal;
+pub mod gas_pool;
 pub mod indexer;
+pub mod resolvers;
+pub mod tx_jobs;
@@ -0,0 +1,202 @@
+//! Automated Resolver Cron Job
+//! Periodically evaluates active market resolvers whose `evaluate_at` has
+//! passed and resolves their markets through the oracle path
+
+use crate::audit;
+use crate::entities::contract;
+use crate::entities::market_resolver::{self, ResolverStatus};
+use crate::entities::tx_job::{self, JobStatus};
+use crate::handlers::{oracle, resolution};
+use crate::jobs::{self, JobRun};
+use crate::resolver::{ResolverConfig, ResolverCronConfig};
+use crate::tx::TxError;
+use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
+use tokio::time;
+
+pub async fn run_resolvers(db: DatabaseConnection) {
+    let config = ResolverCronConfig::from_env();
+    if config.interval.is_zero() {
+        println!("Resolvers: disabled (RESOLVER_INTERVAL_SECS=0)");
+        return;
+    }
+    println!(
+        "Starting Automated Resolver Task (every {}s)...",
+        config.interval.as_secs()
+    );
+
+    let mut interval = time::interval(config.interval);
+
+    loop {
+        interval.tick().await;
+
+        let resolvers = match market_resolver::Entity::find()
+            .filter(market_resolver::Column::Status.eq(ResolverStatus::Active))
+            .all(&db)
+            .await
+        {
+            Ok(r) => r,
+            Err(e) => {
+                eprintln!("Resolvers: Failed to fetch resolvers: {}", e);
+                continue;
+            }
+        };
+
+        let now = chrono::Utc::now();
+        for model in resolvers {
+            // A resolution still being confirmed settles with its job
+            if let Some(job_id) = model.job_id {
+                settle(&db, model, job_id).await;
+                continue;
+            }
+            let due =
+                chrono::DateTime::parse_from_rfc3339(&model.evaluate_at).is_ok_and(|at| now >= at);
+            if !due {
+                continue;
+            }
+            // Deleting a market removes its resolvers; never resolve one left behind
+            match contract::Entity::find_by_id(model.contract_id)
+                .one(&db)
+                .await
+            {
+                Ok(Some(_)) => {}
+                Ok(None) => {
+                    eprintln!(
+                        "Resolvers: Skipping resolver {}; market {} is no longer registered",
+                        model.id, model.market_address
+                    );
+                    continue;
+                }
+                Err(e) => {
+                    eprintln!("Resolvers: Failed to fetch contract: {}", e);
+                    continue;
+                }
+            }
+            // A manual resolution or cancellation may be under way
+            if jobs::open_job_for(&db, &model.market_address)
+                .await
+                .is_some()
+                || resolution::awaiting_for(&db, &model.market_address)
+                    .await
+                    .is_some()
+            {
+                continue;
+            }
+            evaluate(&db, model).await;
+        }
+    }
+}
+
+async fn evaluate(db: &DatabaseConnection, model: market_resolver::Model) {
+    let id = model.id;
+    let market_id = model.market_address.clone();
+
+    let decision = match serde_json::from_str::<ResolverConfig>(&model.config) {
+        Ok(config) => config.evaluate().await,
+        Err(e) => Err(format!("Invalid resolver config: {}", e)),
+    };
+
+    let mut active: market_resolver::ActiveModel = model.into();
+    active.last_checked_at = Set(Some(chrono::Utc::now().to_rfc3339()));
+    active.updated_at = Set(chrono::Utc::now().to_rfc3339());
+
+    match decision {
+        // Not decided yet; ask again on the next run
+        Ok(None) => active.last_error = Set(None),
+        Err(e) => {
+            eprintln!("Resolvers: Resolver {} for {} failed: {}", id, market_id, e);
+            active.last_error = Set(Some(e));
+        }
+        Ok(Some(decision)) => {
+            println!(
+                "Resolvers: Resolver {} resolving market {} with winner {}...",
+                id, market_id, decision.winner
+            );
+            // The job records the audit entry
+            let result = oracle::execute_resolve_market(
+                db,
+                audit::CRON_RESOLVER,
+                &market_id,
+                decision.winner,
+                decision.source,
+            )
+            .await;
+
+            match result {
+                Ok(JobRun::Confirmed { job, outcome }) => {
+                    println!(
+                        "Resolvers: Resolved market {} (Digest: {})",
+                        market_id, outcome.digest
+                    );
+                    active.status = Set(ResolverStatus::Resolved);
+                    active.job_id = Set(Some(job.id));
+                    active.last_error = Set(None);
+                }
+                // Stays active until the job confirms or fails
+                Ok(JobRun::Pending(job)) => {
+                    println!(
+                        "Resolvers: Resolution of market {} pending (job {})",
+                        market_id, job.id
+                    );
+                    active.job_id = Set(Some(job.id));
+                    active.last_error = Set(None);
+                }
+                Err(e) => {
+                    eprintln!("Resolvers: Failed to resolve market {}: {}", market_id, e);
+                    // The contract refused the resolution; retrying cannot help
+                    if matches!(
+                        e,
+                        TxError::Rejected { .. } | TxError::Aborted { .. } | TxError::Failed { .. }
+                    ) {
+                        active.status = Set(ResolverStatus::Failed);
+                    }
+                    active.last_error = Set(Some(e.to_string()));
+                }
+            }
+        }
+    }
+
+    if let Err(e) = active.update(db).await {
+        eprintln!("Resolvers: Failed to update resolver {}: {}", id, e);
+    }
+}
+
+/// Mark a resolver resolved or failed once its resolution job has finished
+async fn settle(db: &DatabaseConnection, model: market_resolver::Model, job_id: i32) {
+    let id = model.id;
+    let job = match tx_job::Entity::find_by_id(job_id).one(db).await {
+        Ok(job) => job,
+        Err(e) => {
+            eprintln!("Resolvers: Failed to fetch job {}: {}", job_id, e);
+            return;
+        }
+    };
+    let (status, error) = match job {
+        Some(job) => match job.status {
+            JobStatus::Pending | JobStatus::Submitted => return,
+            JobStatus::Confirmed => (ResolverStatus::Resolved, None),
+            JobStatus::Failed => (
+                ResolverStatus::Failed,
+                Some(
+                    job.last_error
+                        .unwrap_or_else(|| format!("Job {} failed", job_id)),
+                ),
+            ),
+        },
+        None => (
+            ResolverStatus::Failed,
+            Some(format!("Job {} no longer exists", job_id)),
+        ),
+    };
+    println!(
+        "Resolvers: Resolver {} is {:?} (job {})",
+        id, status, job_id
+    );
+
+    let mut active: market_resolver::ActiveModel = model.into();
+    active.status = Set(status);
+    active.last_error = Set(error);
+    active.updated_at = Set(chrono::Utc::now().to_rfc3339());
+    if let Err(e) = active.update(db).await {
+        eprintln!("Resolvers: Failed to update resolver {}: {}", id, e);
+    }
+}
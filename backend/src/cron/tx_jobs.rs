@@ -0,0 +1,30 @@
+//! Transaction Job Worker Cron Job
+//! Reconciles submitted transactions by digest, resends lost ones and
+//! picks up jobs interrupted before they were signed
+
+use crate::jobs;
+use crate::tx::Executor;
+use sea_orm::DatabaseConnection;
+use std::time::Duration;
+use tokio::time;
+
+pub async fn run_tx_jobs(db: DatabaseConnection) {
+    println!("Starting Transaction Job Worker Task...");
+
+    // Check every 10 seconds
+    let mut interval = time::interval(Duration::from_secs(10));
+
+    loop {
+        interval.tick().await;
+
+        let executor = match Executor::from_env().await {
+            Ok(executor) => executor,
+            Err(e) => {
+                eprintln!("TxJob: {}", e);
+                continue;
+            }
+        };
+
+        jobs::reconcile(&db, &executor).await;
+    }
+}
@@ -1,8 +1,14 @@
-use crate::entities::{category, contract, favorite, market_history};
+use crate::auth;
+use crate::entities::{
+    account, audit_log, auth_nonce, category, contract, favorite, fee_withdrawal, idempotency_key,
+    market_history, market_resolution, market_resolver, pending_resolution, session, sponsored_tx,
+    tx_job,
+};
 use sea_orm::{
-    ActiveModelTrait, ConnectionTrait, Database, DatabaseConnection, DbBackend, EntityTrait,
-    PaginatorTrait, Schema, Set,
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, Database, DatabaseConnection, DbBackend,
+    EntityTrait, PaginatorTrait, QueryFilter, Schema, Set, Statement,
 };
+use std::collections::HashSet;
 
 pub async fn init_db(db_url: &str) -> Result<DatabaseConnection, Box<dyn std::error::Error>> {
     let db: DatabaseConnection = Database::connect(db_url).await?;
@@ -25,6 +31,50 @@ pub async fn init_db(db_url: &str) -> Result<DatabaseConnection, Box<dyn std::er
         .create_table_from_entity(favorite::Entity)
         .if_not_exists()
         .to_owned();
+    let create_table_auth_nonce = schema
+        .create_table_from_entity(auth_nonce::Entity)
+        .if_not_exists()
+        .to_owned();
+    let create_table_session = schema
+        .create_table_from_entity(session::Entity)
+        .if_not_exists()
+        .to_owned();
+    let create_table_audit_log = schema
+        .create_table_from_entity(audit_log::Entity)
+        .if_not_exists()
+        .to_owned();
+    let create_table_account = schema
+        .create_table_from_entity(account::Entity)
+        .if_not_exists()
+        .to_owned();
+    let create_table_tx_job = schema
+        .create_table_from_entity(tx_job::Entity)
+        .if_not_exists()
+        .to_owned();
+    let create_table_fee_withdrawal = schema
+        .create_table_from_entity(fee_withdrawal::Entity)
+        .if_not_exists()
+        .to_owned();
+    let create_table_pending_resolution = schema
+        .create_table_from_entity(pending_resolution::Entity)
+        .if_not_exists()
+        .to_owned();
+    let create_table_market_resolution = schema
+        .create_table_from_entity(market_resolution::Entity)
+        .if_not_exists()
+        .to_owned();
+    let create_table_market_resolver = schema
+        .create_table_from_entity(market_resolver::Entity)
+        .if_not_exists()
+        .to_owned();
+    let create_table_idempotency_key = schema
+        .create_table_from_entity(idempotency_key::Entity)
+        .if_not_exists()
+        .to_owned();
+    let create_table_sponsored_tx = schema
+        .create_table_from_entity(sponsored_tx::Entity)
+        .if_not_exists()
+        .to_owned();
 
     let builder = db.get_database_backend();
 
@@ -32,13 +82,162 @@ pub async fn init_db(db_url: &str) -> Result<DatabaseConnection, Box<dyn std::er
     db.execute(builder.build(&create_table_contract)).await?;
     db.execute(builder.build(&create_table_history)).await?;
     db.execute(builder.build(&create_table_favorite)).await?;
+    db.execute(builder.build(&create_table_auth_nonce)).await?;
+    db.execute(builder.build(&create_table_session)).await?;
+    db.execute(builder.build(&create_table_audit_log)).await?;
+    db.execute(builder.build(&create_table_account)).await?;
+    db.execute(builder.build(&create_table_tx_job)).await?;
+    db.execute(builder.build(&create_table_fee_withdrawal))
+        .await?;
+    db.execute(builder.build(&create_table_pending_resolution))
+        .await?;
+    db.execute(builder.build(&create_table_market_resolution))
+        .await?;
+    db.execute(builder.build(&create_table_market_resolver))
+        .await?;
+    db.execute(builder.build(&create_table_idempotency_key))
+        .await?;
+    db.execute(builder.build(&create_table_sponsored_tx))
+        .await?;
+
+    // Columns added after the first release (CREATE TABLE IF NOT EXISTS skips existing tables)
+    add_column_if_missing(&db, "contracts", "oracle_address", "TEXT").await?;
+    add_column_if_missing(&db, "contracts", "platform_fee_bps", "INTEGER").await?;
+    add_column_if_missing(&db, "contracts", "archived_at", "TEXT").await?;
+    add_column_if_missing(&db, "contracts", "idempotency_key", "TEXT").await?;
+    add_column_if_missing(&db, "tx_jobs", "idempotency_key", "TEXT").await?;
+    add_column_if_missing(&db, "pending_resolutions", "source_url", "TEXT").await?;
+    add_column_if_missing(&db, "pending_resolutions", "notes", "TEXT").await?;
+
+    // One row per market, at most one open job per market, and one open fee
+    // withdrawal at a time
+    create_unique_index(&db, "idx_contracts_address", "contracts (address)").await;
+    create_unique_index(
+        &db,
+        "idx_tx_jobs_open_market",
+        "tx_jobs (market_address) WHERE status IN ('pending', 'submitted')",
+    )
+    .await;
+    create_unique_index(
+        &db,
+        "idx_tx_jobs_open_withdrawal",
+        "tx_jobs (kind) WHERE kind = 'withdraw_platform_fees' \
+         AND status IN ('pending', 'submitted')",
+    )
+    .await;
+
+    // Favorites saved before wallets were checked may hold other spellings
+    normalize_favorite_wallets(&db).await?;
 
     // Seed Categories
     seed_categories(&db).await?;
 
+    // Bootstrap super admins from ADMIN_ADDRESSES
+    seed_admin_accounts(&db).await?;
+
     Ok(db)
 }
 
+async fn add_column_if_missing(
+    db: &DatabaseConnection,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let rows = db
+        .query_all(Statement::from_string(
+            DbBackend::Sqlite,
+            format!("PRAGMA table_info({})", table),
+        ))
+        .await?;
+
+    let exists = rows
+        .iter()
+        .any(|row| row.try_get::<String>("", "name").ok().as_deref() == Some(column));
+
+    if !exists {
+        db.execute_unprepared(&format!(
+            "ALTER TABLE {} ADD COLUMN {} {}",
+            table, column, definition
+        ))
+        .await?;
+        println!("Migrated: added {}.{}", table, column);
+    }
+    Ok(())
+}
+
+/// Existing duplicate rows keep an index from being built; they have to be
+/// cleaned up by hand, so warn instead of refusing to start
+async fn create_unique_index(db: &DatabaseConnection, name: &str, definition: &str) {
+    let sql = format!(
+        "CREATE UNIQUE INDEX IF NOT EXISTS {} ON {}",
+        name, definition
+    );
+    if let Err(e) = db.execute_unprepared(&sql).await {
+        eprintln!("Warning: could not create unique index {}: {}", name, e);
+    }
+}
+
+async fn normalize_favorite_wallets(
+    db: &DatabaseConnection,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut seen = HashSet::new();
+    let mut favorites = favorite::Entity::find().all(db).await?;
+    // Canonical rows first, so they are the ones kept
+    favorites.sort_by_key(|f| {
+        !auth::parse_address(&f.wallet_address)
+            .is_ok_and(|wallet| wallet.to_string() == f.wallet_address)
+    });
+    for fav in favorites {
+        let Ok(wallet) = auth::parse_address(&fav.wallet_address) else {
+            continue;
+        };
+        let wallet = wallet.to_string();
+        if !seen.insert((wallet.clone(), fav.contract_id)) {
+            favorite::Entity::delete_by_id(fav.id).exec(db).await?;
+        } else if wallet != fav.wallet_address {
+            let mut active: favorite::ActiveModel = fav.into();
+            active.wallet_address = Set(wallet);
+            active.update(db).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Seed `ADMIN_ADDRESSES` into an empty `accounts` table only; afterwards
+/// accounts are managed through the API, so a removed admin stays removed
+async fn seed_admin_accounts(db: &DatabaseConnection) -> Result<(), Box<dyn std::error::Error>> {
+    let seeded = account::Entity::find().count(db).await? > 0;
+    for address in auth::admin_addresses() {
+        let exists = account::Entity::find()
+            .filter(account::Column::Address.eq(address.to_string()))
+            .count(db)
+            .await?;
+        if exists > 0 {
+            continue;
+        }
+        if seeded {
+            eprintln!(
+                "Warning: ADMIN_ADDRESSES lists {} but it has no account; not re-adding it. \
+                 Add it through /admin/accounts or remove it from ADMIN_ADDRESSES",
+                address
+            );
+        } else {
+            account::ActiveModel {
+                address: Set(address.to_string()),
+                role: Set(account::Role::SuperAdmin),
+                label: Set(Some("ADMIN_ADDRESSES".to_string())),
+                created_at: Set(chrono::Utc::now().to_rfc3339()),
+                ..Default::default()
+            }
+            .insert(db)
+            .await?;
+            println!("Seeded super admin account {}", address);
+        }
+    }
+    Ok(())
+}
+
 async fn seed_categories(db: &DatabaseConnection) -> Result<(), Box<dyn std::error::Error>> {
     let count = category::Entity::find().count(db).await?;
     if count == 0 {
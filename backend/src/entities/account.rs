@@ -0,0 +1,48 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "Text")]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    /// Everything, including managing accounts
+    #[sea_orm(string_value = "super_admin")]
+    SuperAdmin,
+    /// Create, import and delete markets
+    #[sea_orm(string_value = "market_creator")]
+    MarketCreator,
+    /// Resolve and cancel markets
+    #[sea_orm(string_value = "oracle")]
+    Oracle,
+    /// Read-only access to admin queries
+    #[sea_orm(string_value = "viewer")]
+    Viewer,
+}
+
+impl Role {
+    /// Whether an account holding `self` may call an endpoint requiring `required`
+    pub fn allows(self, required: Role) -> bool {
+        self == Role::SuperAdmin || self == required || required == Role::Viewer
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
+#[schema(as = Account)]
+#[sea_orm(table_name = "accounts")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    #[sea_orm(unique)]
+    pub address: String,
+    pub role: Role,
+    pub label: Option<String>,
+    pub created_at: String, // ISO date string
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,26 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
+#[schema(as = AuditLog)]
+#[sea_orm(table_name = "audit_log")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub created_at: String, // ISO date string
+    pub actor: String,      // Admin address, or "cron:<job>" for background jobs
+    #[sea_orm(indexed)]
+    pub action: String, // create_market, delete_market, resolve_market, cancel_market, ...
+    pub contract_id: Option<i32>,
+    pub market_address: Option<String>,
+    pub payload: Option<String>, // JSON string of the request
+    pub tx_digest: Option<String>,
+    pub outcome: String, // "success" or "failure"
+    pub error: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -1,7 +1,9 @@
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
+#[schema(as = Contract)]
 #[sea_orm(table_name = "contracts")]
 pub struct Model {
     #[sea_orm(primary_key)]
@@ -18,6 +20,11 @@ pub struct Model {
     pub resolved: bool,               // Whether market has been resolved by oracle
     pub winner: Option<i32>,          // Winning option index (0, 1, 2, etc.)
     pub cancelled: bool,              // Whether market was cancelled (refund mode)
+    pub oracle_address: Option<String>, // Address allowed to resolve on-chain
+    pub platform_fee_bps: Option<i32>, // Fee charged on each bet, read from chain on import
+    pub archived_at: Option<String>,  // Hidden from listings but still indexed when set
+    #[serde(skip)]
+    pub idempotency_key: Option<String>, // Idempotency-Key of the request that added it
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -0,0 +1,23 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
+#[schema(as = FeeWithdrawal)]
+#[sea_orm(table_name = "fee_withdrawals")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub contract_id: Option<i32>,
+    #[sea_orm(indexed)]
+    pub market_address: String,
+    pub amount: i64, // MIST, the market's platform_balance just before the withdrawal
+    pub digest: String,
+    pub job_id: i32,
+    pub created_at: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
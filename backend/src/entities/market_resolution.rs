@@ -0,0 +1,26 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
+#[schema(as = MarketResolution)]
+#[sea_orm(table_name = "market_resolutions")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub contract_id: Option<i32>,
+    #[sea_orm(indexed)]
+    pub market_address: String,
+    pub winner: i32,
+    pub outcome_label: Option<String>, // Label of `winner` in the market's options
+    pub source_url: Option<String>,    // Where the operator took the result from
+    pub notes: Option<String>,
+    pub digest: String,
+    pub resolved_by: String, // Session address or cron actor that requested it
+    pub created_at: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
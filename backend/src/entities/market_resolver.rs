@@ -0,0 +1,50 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "Text")]
+#[serde(rename_all = "snake_case")]
+pub enum ResolverStatus {
+    /// Evaluated by the resolver cron once `evaluate_at` has passed; with
+    /// `job_id` set, waiting for that resolution job to finish
+    #[sea_orm(string_value = "active")]
+    Active,
+    /// Resolution job (`job_id`) confirmed
+    #[sea_orm(string_value = "resolved")]
+    Resolved,
+    /// The market could not be resolved, e.g. it was settled elsewhere or the job failed
+    #[sea_orm(string_value = "failed")]
+    Failed,
+    #[sea_orm(string_value = "disabled")]
+    Disabled,
+}
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
+#[schema(as = MarketResolver)]
+#[sea_orm(table_name = "market_resolvers")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub contract_id: i32,
+    #[sea_orm(indexed)]
+    pub market_address: String,
+    pub kind: String,   // http_threshold, scheduled_manual
+    pub config: String, // JSON resolver settings, see `resolver::ResolverConfig`
+    #[sea_orm(indexed)]
+    pub status: ResolverStatus,
+    pub evaluate_at: String, // RFC 3339; not evaluated before this
+    pub last_checked_at: Option<String>,
+    pub last_error: Option<String>,
+    pub job_id: Option<i32>,
+    pub created_by: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -1,4 +1,15 @@
+pub mod account;
+pub mod audit_log;
+pub mod auth_nonce;
 pub mod category;
 pub mod contract;
 pub mod favorite;
+pub mod fee_withdrawal;
+pub mod idempotency_key;
 pub mod market_history;
+pub mod market_resolution;
+pub mod market_resolver;
+pub mod pending_resolution;
+pub mod session;
+pub mod sponsored_tx;
+pub mod tx_job;
@@ -0,0 +1,54 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "Text")]
+#[serde(rename_all = "snake_case")]
+pub enum ResolutionStatus {
+    /// Unsigned transaction handed out; waiting for the oracle's signature
+    #[sea_orm(string_value = "awaiting_signature")]
+    AwaitingSignature,
+    /// Signed transaction sent as a job; settles when the job confirms or fails
+    #[sea_orm(string_value = "submitting")]
+    Submitting,
+    #[sea_orm(string_value = "executed")]
+    Executed,
+    /// Aborted on chain; prepare a new resolution to retry
+    #[sea_orm(string_value = "failed")]
+    Failed,
+    /// Discarded before execution
+    #[sea_orm(string_value = "abandoned")]
+    Abandoned,
+}
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
+#[schema(as = PendingResolution)]
+#[sea_orm(table_name = "pending_resolutions")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub contract_id: Option<i32>,
+    #[sea_orm(indexed)]
+    pub market_address: String,
+    pub winner: i32,
+    pub sender: String, // Oracle address that signs and pays gas, e.g. a multisig
+    pub tx_bytes: String, // Base64 BCS TransactionData to sign
+    pub digest: String, // Digest the signed transaction will have
+    pub gas_budget: i64,
+    pub source_url: Option<String>, // Recorded with the resolution once executed
+    pub notes: Option<String>,
+    #[sea_orm(indexed)]
+    pub status: ResolutionStatus,
+    pub requested_by: String,
+    pub last_error: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,23 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
+#[schema(as = SponsoredTx)]
+#[sea_orm(table_name = "sponsored_txs")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    #[sea_orm(indexed)]
+    pub wallet: String,
+    pub functions: String, // Comma-separated `market` functions the transaction calls
+    pub digest: Option<String>, // None while the transaction is being built
+    pub gas_budget: i64,   // MIST counted against the wallet's quota
+    #[sea_orm(indexed)]
+    pub created_at: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,52 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "Text")]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    /// Recorded, not yet signed
+    #[sea_orm(string_value = "pending")]
+    Pending,
+    /// Signed and sent at least once; outcome not yet known
+    #[sea_orm(string_value = "submitted")]
+    Submitted,
+    #[sea_orm(string_value = "confirmed")]
+    Confirmed,
+    #[sea_orm(string_value = "failed")]
+    Failed,
+}
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
+#[schema(as = TxJob)]
+#[sea_orm(table_name = "tx_jobs")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub kind: String, // create_market, resolve_market, cancel_market
+    pub actor: String,
+    pub contract_id: Option<i32>,
+    #[sea_orm(indexed)]
+    pub market_address: Option<String>,
+    pub spec: String, // JSON job arguments, enough to rebuild the transaction
+    #[sea_orm(indexed)]
+    pub status: JobStatus,
+    pub digest: Option<String>,
+    #[serde(skip)]
+    pub tx_bytes: Option<String>, // Base64 BCS of the signed transaction, for resending
+    pub gas_budget: Option<i64>,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub result: Option<String>, // JSON: digest, gas and kind-specific output
+    pub idempotency_key: Option<String>, // Idempotency-Key of the request that started it
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,197 @@
+//! Platform fee withdrawal.
+//!
+//! Each market keeps its fees in `platform_balance` until the `platform_admin`
+//! calls `withdraw_platform_fees`. Markets whose admin is the backend signer
+//! are withdrawn in batches, one programmable transaction per batch, and each
+//! market's withdrawn amount is recorded in `fee_withdrawals` once the job
+//! confirms.
+
+use crate::chain::{self, MarketLookupError};
+use crate::entities::{contract, fee_withdrawal, tx_job};
+use crate::jobs::{self, JobRun, JobSpec};
+use crate::tx::{self, Executor, TxError, TxOutcome};
+use sea_orm::{ActiveModelTrait, DatabaseConnection, EntityTrait, Set};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use std::time::Duration;
+use sui_sdk::{types::base_types::ObjectID, SuiClient};
+use utoipa::ToSchema;
+
+pub struct FeeConfig {
+    pub min_amount: u64, // Smaller balances are not worth the gas
+    pub batch_size: usize,
+    pub interval: Duration,
+}
+
+impl FeeConfig {
+    pub fn from_env() -> Self {
+        fn env_u64(key: &str, default: u64) -> u64 {
+            std::env::var(key)
+                .ok()
+                .and_then(|v| v.trim().parse().ok())
+                .unwrap_or(default)
+        }
+        FeeConfig {
+            min_amount: env_u64("FEE_WITHDRAW_MIN_MIST", 10_000_000),
+            batch_size: env_u64("FEE_WITHDRAW_BATCH_SIZE", 50).max(1) as usize,
+            interval: Duration::from_secs(env_u64("FEE_WITHDRAW_INTERVAL_SECS", 3600)),
+        }
+    }
+}
+
+/// A market with fees the backend signer can withdraw
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct Withdrawable {
+    pub market_id: String,
+    pub contract_id: Option<i32>,
+    /// `platform_balance` in MIST when read
+    pub amount: u64,
+}
+
+/// Registered markets holding at least `min_amount` in fees for the signer,
+/// optionally limited to `only`
+pub async fn find_withdrawable(
+    db: &DatabaseConnection,
+    executor: &Executor,
+    only: Option<&[ObjectID]>,
+    min_amount: u64,
+) -> Result<Vec<Withdrawable>, TxError> {
+    let package_id = chain::package_id().map_err(TxError::Config)?;
+    let contracts = contract::Entity::find()
+        .all(db)
+        .await
+        .map_err(|e| TxError::Config(format!("Failed to fetch contracts: {}", e)))?;
+
+    let mut found = Vec::new();
+    for contract_model in contracts {
+        let Ok(market_id) = ObjectID::from_str(contract_model.address.trim()) else {
+            continue;
+        };
+        if only.is_some_and(|ids| !ids.contains(&market_id)) {
+            continue;
+        }
+
+        let market = match chain::fetch_market(executor.client(), package_id, market_id).await {
+            Ok(market) => market,
+            Err(MarketLookupError::Rpc(e)) => return Err(TxError::Submit(e)),
+            Err(MarketLookupError::Invalid(e)) => {
+                eprintln!("Fees: skipping contract {}: {}", contract_model.id, e);
+                continue;
+            }
+        };
+
+        // The contract aborts with ENoFees on an empty balance
+        if market.platform_admin == executor.sender()
+            && market.platform_balance >= min_amount.max(1)
+        {
+            found.push(Withdrawable {
+                market_id: contract_model.address,
+                contract_id: Some(contract_model.id),
+                amount: market.platform_balance,
+            });
+        }
+    }
+    Ok(found)
+}
+
+/// Withdraw up to one batch of fees. `Ok(None)` when nothing qualifies.
+pub async fn withdraw(
+    db: &DatabaseConnection,
+    actor: impl ToString,
+    only: Option<&[ObjectID]>,
+    config: &FeeConfig,
+) -> Result<Option<(Vec<Withdrawable>, JobRun)>, TxError> {
+    let executor = Executor::from_env().await?;
+    let mut markets = find_withdrawable(db, &executor, only, config.min_amount).await?;
+    if markets.is_empty() {
+        return Ok(None);
+    }
+    // Largest balances first, the rest wait for the next batch
+    markets.sort_by_key(|m| std::cmp::Reverse(m.amount));
+    markets.truncate(config.batch_size);
+
+    let spec = JobSpec::WithdrawFees {
+        markets: markets.clone(),
+    };
+    let run = jobs::run(db, actor, spec).await?;
+    Ok(Some((markets, run)))
+}
+
+/// Record one revenue row per market of a confirmed withdrawal job.
+/// `withdraw_platform_fees` empties the balance, so the amount withdrawn is
+/// the market's balance just before the transaction, which also counts fees
+/// paid after `find_withdrawable` read it.
+pub async fn record_revenue(
+    db: &DatabaseConnection,
+    job: &tx_job::Model,
+    markets: &[Withdrawable],
+    outcome: &TxOutcome,
+) -> Result<(), String> {
+    let mut unknown = Vec::new();
+    let chain = match (chain::package_id(), tx::connect().await) {
+        (Ok(package_id), Ok(client)) => Some((package_id, client)),
+        (Err(e), _) => {
+            unknown.push(e);
+            None
+        }
+        (_, Err(e)) => {
+            unknown.push(e.to_string());
+            None
+        }
+    };
+
+    for market in markets {
+        let amount = match &chain {
+            Some((package_id, client)) => {
+                match withdrawn_amount(client, *package_id, market, outcome).await {
+                    Ok(amount) => amount,
+                    Err(e) => {
+                        unknown.push(format!("{}: {}", market.market_id, e));
+                        market.amount
+                    }
+                }
+            }
+            None => market.amount,
+        };
+        fee_withdrawal::ActiveModel {
+            contract_id: Set(market.contract_id),
+            market_address: Set(market.market_id.clone()),
+            amount: Set(amount as i64),
+            digest: Set(outcome.digest.clone()),
+            job_id: Set(job.id),
+            created_at: Set(chrono::Utc::now().to_rfc3339()),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+        .map_err(|e| format!("Fees withdrawn but revenue not recorded: {}", e))?;
+    }
+
+    if unknown.is_empty() {
+        Ok(())
+    } else {
+        Err(format!(
+            "Recorded the balances read before withdrawing; withdrawn amounts unknown: {}",
+            unknown.join("; ")
+        ))
+    }
+}
+
+/// `platform_balance` of `market` at the version the withdrawal modified
+async fn withdrawn_amount(
+    client: &SuiClient,
+    package_id: ObjectID,
+    market: &Withdrawable,
+    outcome: &TxOutcome,
+) -> Result<u64, String> {
+    let market_id = ObjectID::from_str(&market.market_id).map_err(|e| e.to_string())?;
+    let (_, version) = outcome
+        .modified_at_versions
+        .iter()
+        .find(|(id, _)| *id == market_id)
+        .ok_or_else(|| "not modified by the transaction".to_string())?;
+    chain::fetch_market_at(client, package_id, market_id, *version)
+        .await
+        .map(|m| m.platform_balance)
+        .map_err(|e| e.to_string())
+}
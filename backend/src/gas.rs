@@ -0,0 +1,246 @@
+//! Gas coin selection for the backend signer.
+//!
+//! Coins handed to an in-flight transaction are reserved until it completes, so
+//! concurrent admin transactions never equivocate on the same gas object. The
+//! gas manager cron keeps a pool of coins big enough for one transaction each,
+//! smashing dust into them along the way.
+
+use crate::tx::{Executor, TxError};
+use std::collections::HashSet;
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+use sui_sdk::{
+    rpc_types::Coin,
+    types::{
+        base_types::{ObjectID, ObjectRef, SuiAddress},
+        programmable_transaction_builder::ProgrammableTransactionBuilder,
+        transaction::{Argument, TransactionData},
+    },
+    SuiClient,
+};
+
+static RESERVED: LazyLock<Mutex<HashSet<ObjectID>>> = LazyLock::new(|| Mutex::new(HashSet::new()));
+
+// Protocol limit on gas payment objects per transaction
+const MAX_GAS_PAYMENT_COINS: usize = 256;
+// How long `reserve_gas` waits for another transaction to free a coin
+const RESERVE_ATTEMPTS: u32 = 10;
+const RESERVE_RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// Coins reserved for one transaction; released on drop
+pub struct GasLease {
+    coins: Vec<ObjectID>,
+}
+
+impl GasLease {
+    /// Keep the coins reserved past this lease, e.g. while a submission's
+    /// outcome is unknown; free them later with `release`
+    pub fn keep(mut self) {
+        self.coins.clear();
+    }
+
+    /// Keep the coins reserved for `duration`, e.g. while a user co-signs a
+    /// sponsored transaction that the backend never sees executed
+    pub fn keep_for(mut self, duration: Duration) {
+        let coins = std::mem::take(&mut self.coins);
+        tokio::spawn(async move {
+            tokio::time::sleep(duration).await;
+            release(&coins);
+        });
+    }
+}
+
+/// Reserve specific coins, such as those of a transaction awaiting reconciliation
+pub fn hold(coins: &[ObjectID]) {
+    RESERVED.lock().unwrap().extend(coins.iter().copied());
+}
+
+pub fn release(coins: &[ObjectID]) {
+    let mut reserved = RESERVED.lock().unwrap();
+    for coin in coins {
+        reserved.remove(coin);
+    }
+}
+
+impl Drop for GasLease {
+    fn drop(&mut self) {
+        release(&self.coins);
+    }
+}
+
+/// Reserve every free coin matching `pick`, or nothing when `pick` returns an empty list
+fn try_reserve(
+    coins: &[Coin],
+    pick: impl FnOnce(Vec<&Coin>) -> Vec<&Coin>,
+) -> Option<(Vec<ObjectRef>, GasLease)> {
+    let mut reserved = RESERVED.lock().unwrap();
+    let free = coins
+        .iter()
+        .filter(|c| !reserved.contains(&c.coin_object_id))
+        .collect();
+    let picked = pick(free);
+    if picked.is_empty() {
+        return None;
+    }
+    for coin in &picked {
+        reserved.insert(coin.coin_object_id);
+    }
+    Some((
+        picked.iter().map(|c| c.object_ref()).collect(),
+        GasLease {
+            coins: picked.iter().map(|c| c.coin_object_id).collect(),
+        },
+    ))
+}
+
+pub async fn owned_coins(client: &SuiClient, owner: SuiAddress) -> Result<Vec<Coin>, TxError> {
+    let mut coins = Vec::new();
+    let mut cursor = None;
+    loop {
+        let page = client
+            .coin_read_api()
+            .get_coins(owner, None, cursor, None)
+            .await
+            .map_err(|e| TxError::Submit(format!("Failed to list gas coins: {}", e)))?;
+        coins.extend(page.data);
+        if !page.has_next_page {
+            break;
+        }
+        cursor = page.next_cursor;
+    }
+    Ok(coins)
+}
+
+/// Smallest free coin that covers `budget`, keeping big coins for pool refills
+pub async fn reserve_gas(
+    client: &SuiClient,
+    owner: SuiAddress,
+    budget: u64,
+) -> Result<(ObjectRef, GasLease), TxError> {
+    for attempt in 1..=RESERVE_ATTEMPTS {
+        let coins = owned_coins(client, owner).await?;
+        if !coins.iter().any(|c| c.balance >= budget) {
+            return Err(TxError::Build(format!(
+                "No gas coin of {} holds at least {} MIST",
+                owner, budget
+            )));
+        }
+
+        let lease = try_reserve(&coins, |free| {
+            free.into_iter()
+                .filter(|c| c.balance >= budget)
+                .min_by_key(|c| c.balance)
+                .into_iter()
+                .collect()
+        });
+        if let Some((mut refs, lease)) = lease {
+            return Ok((refs.remove(0), lease));
+        }
+
+        if attempt < RESERVE_ATTEMPTS {
+            tokio::time::sleep(RESERVE_RETRY_DELAY).await;
+        }
+    }
+
+    Err(TxError::Build(
+        "All gas coins are in use by other transactions".to_string(),
+    ))
+}
+
+pub struct GasPoolConfig {
+    pub pool_size: usize,
+    pub coin_balance: u64, // Target MIST per pool coin
+    pub dust_threshold: u64,
+    pub interval: Duration,
+}
+
+impl GasPoolConfig {
+    pub fn from_env() -> Self {
+        fn env_u64(key: &str, default: u64) -> u64 {
+            std::env::var(key)
+                .ok()
+                .and_then(|v| v.trim().parse().ok())
+                .unwrap_or(default)
+        }
+        GasPoolConfig {
+            pool_size: env_u64("GAS_POOL_SIZE", 4) as usize,
+            coin_balance: env_u64("GAS_POOL_COIN_MIST", 200_000_000),
+            dust_threshold: env_u64("GAS_DUST_MIST", 50_000_000),
+            interval: Duration::from_secs(env_u64("GAS_POOL_INTERVAL_SECS", 300)),
+        }
+    }
+}
+
+/// Merge dust and split pool coins in one transaction, when needed.
+/// Returns the digest when a transaction was sent.
+pub async fn maintain_pool(
+    executor: &Executor,
+    config: &GasPoolConfig,
+) -> Result<Option<String>, TxError> {
+    let client = executor.client();
+    let owner = executor.sender();
+    let coins = owned_coins(client, owner).await?;
+
+    let pool_coins = coins
+        .iter()
+        .filter(|c| c.balance >= config.coin_balance)
+        .count();
+    let dust_coins = coins
+        .iter()
+        .filter(|c| c.balance < config.dust_threshold)
+        .count();
+    let missing = config.pool_size.saturating_sub(pool_coins);
+
+    if missing == 0 && dust_coins < 2 {
+        return Ok(None);
+    }
+
+    // The largest free coin plus free dust become gas payment; the transaction
+    // smashes them into the first, and pool coins are split from the result
+    let Some((gas_payment, _lease)) = try_reserve(&coins, |mut free| {
+        free.sort_by_key(|c| std::cmp::Reverse(c.balance));
+        let mut free = free.into_iter();
+        free.next()
+            .into_iter()
+            .chain(free.filter(|c| c.balance < config.dust_threshold))
+            .take(MAX_GAS_PAYMENT_COINS)
+            .collect()
+    }) else {
+        return Ok(None);
+    };
+    let merged = gas_payment.len() - 1;
+
+    let budget = executor.gas_budget();
+    let free_balance: u64 = coins
+        .iter()
+        .filter(|c| gas_payment.iter().any(|r| r.0 == c.coin_object_id))
+        .map(|c| c.balance)
+        .sum();
+    // Leave the budget plus one pool coin's worth in the smashed coin
+    let affordable = free_balance.saturating_sub(budget) / config.coin_balance.max(1);
+    let splits = (missing as u64).min(affordable.saturating_sub(1)) as usize;
+
+    if splits == 0 && merged == 0 {
+        return Ok(None);
+    }
+
+    let mut ptb = ProgrammableTransactionBuilder::new();
+    if splits > 0 {
+        ptb.pay_sui(vec![owner; splits], vec![config.coin_balance; splits])
+            .map_err(|e| TxError::Build(format!("Failed to build pool split: {}", e)))?;
+    } else {
+        // Merge only: gas smashing does the work, the command just gives the PTB a body
+        ptb.transfer_arg(owner, Argument::GasCoin);
+    }
+
+    let gas_price = executor.gas_price().await?;
+    let tx_data =
+        TransactionData::new_programmable(owner, gas_payment, ptb.finish(), budget, gas_price);
+
+    println!(
+        "GasPool: merging {} dust coins, splitting {} pool coins of {} MIST",
+        merged, splits, config.coin_balance
+    );
+    let outcome = executor.submit(tx_data, "gas_pool").await?;
+    Ok(Some(outcome.digest))
+}
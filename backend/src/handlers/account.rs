@@ -0,0 +1,174 @@
+use crate::audit::{self, AuditEntry};
+use crate::auth::{self, AuthSession};
+use crate::entities::account::{self, Role};
+use axum::{
+    extract::{Extension, Path, State},
+    http::StatusCode,
+    Json,
+};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter,
+    QueryOrder, Set,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct UpsertAccount {
+    pub address: String,
+    pub role: Role,
+    pub label: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/accounts",
+    security(("bearer" = [])),
+    tag = "admin",
+    responses(
+        (status = 200, description = "All admin accounts", body = [account::Model]),
+        (status = 403, description = "Caller is not a super admin", body = String),
+    )
+)]
+pub async fn list_accounts(
+    State(db): State<DatabaseConnection>,
+) -> Result<Json<Vec<account::Model>>, (StatusCode, String)> {
+    let accounts = account::Entity::find()
+        .order_by_asc(account::Column::Id)
+        .all(&db)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(accounts))
+}
+
+/// Create an account or change its role
+#[utoipa::path(
+    post,
+    path = "/admin/accounts",
+    security(("bearer" = [])),
+    tag = "admin",
+    request_body = UpsertAccount,
+    responses(
+        (status = 200, description = "Stored account", body = account::Model),
+        (status = 400, description = "Invalid address", body = String),
+        (status = 403, description = "Caller is not a super admin", body = String),
+    )
+)]
+pub async fn upsert_account(
+    State(db): State<DatabaseConnection>,
+    Extension(session): Extension<AuthSession>,
+    Json(payload): Json<UpsertAccount>,
+) -> Result<Json<account::Model>, (StatusCode, String)> {
+    let address = auth::parse_address(&payload.address)?;
+
+    if address == session.address && payload.role != Role::SuperAdmin {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "Super admins cannot demote themselves".to_string(),
+        ));
+    }
+
+    let existing = account::Entity::find()
+        .filter(account::Column::Address.eq(address.to_string()))
+        .one(&db)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let result = match existing {
+        Some(model) => {
+            let mut active: account::ActiveModel = model.into();
+            active.role = Set(payload.role);
+            active.label = Set(payload.label.clone());
+            active.update(&db).await
+        }
+        None => {
+            account::ActiveModel {
+                address: Set(address.to_string()),
+                role: Set(payload.role),
+                label: Set(payload.label.clone()),
+                created_at: Set(chrono::Utc::now().to_rfc3339()),
+                ..Default::default()
+            }
+            .insert(&db)
+            .await
+        }
+    }
+    .map_err(|e| e.to_string());
+
+    audit::record(
+        &db,
+        AuditEntry::new(session.address, "assign_role")
+            .payload(&payload)
+            .error(result.as_ref().err().cloned()),
+    )
+    .await;
+
+    result
+        .map(Json)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/admin/accounts/{address}",
+    security(("bearer" = [])),
+    tag = "admin",
+    params(("address" = String, Path, description = "Account address")),
+    responses(
+        (status = 204, description = "Removed"),
+        (status = 400, description = "Cannot remove yourself or the last super admin", body = String),
+        (status = 404, description = "Account not found", body = String),
+    )
+)]
+pub async fn delete_account(
+    State(db): State<DatabaseConnection>,
+    Extension(session): Extension<AuthSession>,
+    Path(address): Path<String>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    let address = auth::parse_address(&address)?;
+
+    if address == session.address {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "Super admins cannot remove themselves".to_string(),
+        ));
+    }
+
+    let existing = account::Entity::find()
+        .filter(account::Column::Address.eq(address.to_string()))
+        .one(&db)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, "Account not found".to_string()))?;
+
+    if existing.role == Role::SuperAdmin {
+        let super_admins = account::Entity::find()
+            .filter(account::Column::Role.eq(Role::SuperAdmin))
+            .count(&db)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        if super_admins <= 1 {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                "Cannot remove the last super admin".to_string(),
+            ));
+        }
+    }
+
+    let result = account::Entity::delete_by_id(existing.id)
+        .exec(&db)
+        .await
+        .map_err(|e| e.to_string());
+
+    audit::record(
+        &db,
+        AuditEntry::new(session.address, "remove_account")
+            .payload(&existing)
+            .error(result.as_ref().err().cloned()),
+    )
+    .await;
+
+    result.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+    Ok(StatusCode::NO_CONTENT)
+}
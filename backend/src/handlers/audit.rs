@@ -0,0 +1,86 @@
+use crate::entities::audit_log;
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    Json,
+};
+use sea_orm::{
+    ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+const DEFAULT_PAGE_SIZE: u64 = 50;
+const MAX_PAGE_SIZE: u64 = 200;
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AuditLogParams {
+    /// Zero-based page index
+    pub page: Option<u64>,
+    pub page_size: Option<u64>,
+    pub action: Option<String>,
+    pub actor: Option<String>,
+    pub contract_id: Option<i32>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct AuditLogPage {
+    pub items: Vec<audit_log::Model>,
+    pub page: u64,
+    pub page_size: u64,
+    pub total: u64,
+}
+
+/// Newest-first audit trail of admin and cron actions
+#[utoipa::path(
+    get,
+    path = "/admin/audit-log",
+    security(("bearer" = [])),
+    tag = "admin",
+    params(AuditLogParams),
+    responses(
+        (status = 200, description = "One page of audit entries", body = AuditLogPage),
+        (status = 401, description = "Missing or invalid session", body = String),
+        (status = 403, description = "Caller lacks the required role", body = String),
+    )
+)]
+pub async fn list_audit_log(
+    State(db): State<DatabaseConnection>,
+    Query(params): Query<AuditLogParams>,
+) -> Result<Json<AuditLogPage>, (StatusCode, String)> {
+    let page = params.page.unwrap_or(0);
+    let page_size = params
+        .page_size
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+
+    let mut query = audit_log::Entity::find().order_by_desc(audit_log::Column::Id);
+
+    if let Some(action) = params.action {
+        query = query.filter(audit_log::Column::Action.eq(action));
+    }
+    if let Some(actor) = params.actor {
+        query = query.filter(audit_log::Column::Actor.eq(actor));
+    }
+    if let Some(contract_id) = params.contract_id {
+        query = query.filter(audit_log::Column::ContractId.eq(contract_id));
+    }
+
+    let paginator = query.paginate(&db, page_size);
+    let total = paginator
+        .num_items()
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let items = paginator
+        .fetch_page(page)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(AuditLogPage {
+        items,
+        page,
+        page_size,
+        total,
+    }))
+}
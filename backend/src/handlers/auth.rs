@@ -0,0 +1,86 @@
+use crate::auth;
+use crate::entities::account::Role;
+use axum::{extract::State, http::StatusCode, Json};
+use sea_orm::DatabaseConnection;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Deserialize, ToSchema)]
+pub struct ChallengeRequest {
+    pub address: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ChallengeResponse {
+    pub nonce: String,
+    pub message: String, // Sign this exact text with `signPersonalMessage`
+    pub expires_at: String,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct LoginRequest {
+    pub address: String,
+    pub nonce: String,
+    pub signature: String, // Base64 serialized signature returned by the wallet
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct LoginResponse {
+    pub token: String,
+    pub address: String,
+    pub expires_at: String,
+    pub role: Option<Role>, // Admin role from the accounts table, if any
+}
+
+/// Issue a sign-in challenge for a wallet address
+#[utoipa::path(
+    post,
+    path = "/auth/challenge",
+    tag = "auth",
+    request_body = ChallengeRequest,
+    responses(
+        (status = 200, description = "Message to sign", body = ChallengeResponse),
+        (status = 400, description = "Invalid address", body = String),
+    )
+)]
+pub async fn challenge(
+    State(db): State<DatabaseConnection>,
+    Json(payload): Json<ChallengeRequest>,
+) -> Result<Json<ChallengeResponse>, (StatusCode, String)> {
+    let address = auth::parse_address(&payload.address)?;
+    let challenge = auth::create_challenge(&db, address).await?;
+
+    Ok(Json(ChallengeResponse {
+        nonce: challenge.nonce,
+        message: challenge.message,
+        expires_at: challenge.expires_at,
+    }))
+}
+
+/// Exchange a signed challenge for a session token
+#[utoipa::path(
+    post,
+    path = "/auth/login",
+    tag = "auth",
+    request_body = LoginRequest,
+    responses(
+        (status = 200, description = "Session issued", body = LoginResponse),
+        (status = 401, description = "Bad, expired or replayed signature", body = String),
+    )
+)]
+pub async fn login(
+    State(db): State<DatabaseConnection>,
+    Json(payload): Json<LoginRequest>,
+) -> Result<Json<LoginResponse>, (StatusCode, String)> {
+    let address = auth::parse_address(&payload.address)?;
+    auth::consume_challenge(&db, address, &payload.nonce, &payload.signature).await?;
+    let session = auth::create_session(&db, address).await?;
+    let role = auth::account_role(&db, &address).await?;
+
+    Ok(Json(LoginResponse {
+        token: session.token,
+        address: session.address,
+        expires_at: session.expires_at,
+        role,
+    }))
+}
@@ -1,128 +1,111 @@
+use crate::auth::AuthSession;
+use crate::entities::tx_job;
+use crate::jobs::{self, JobRun, JobSpec};
+use crate::preflight;
+use crate::tx::{DryRunParams, Executor, GasCost, MoveCall, TxError, TxErrorBody};
 use axum::{
-    extract::{Json, State},
+    extract::{Extension, Json, Query, State},
     http::StatusCode,
-    response::IntoResponse,
+    response::{IntoResponse, Response},
 };
 use sea_orm::DatabaseConnection;
 use serde::{Deserialize, Serialize};
-use shared_crypto::intent::Intent;
-use std::path::PathBuf;
 use std::str::FromStr;
-use sui_keys::keystore::{AccountKeystore, FileBasedKeystore};
-use sui_sdk::{
-    json::SuiJsonValue,
-    rpc_types::SuiTransactionBlockResponseOptions,
-    types::{base_types::ObjectID, transaction::Transaction},
-    SuiClientBuilder,
-};
+use sui_sdk::{json::SuiJsonValue, types::base_types::ObjectID};
+use utoipa::ToSchema;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, ToSchema)]
 pub struct CancelMarketRequest {
     pub market_id: String,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct CancelMarketResponse {
     pub digest: String,
     pub status: String,
+    pub gas_budget: u64,
+    pub gas_used: GasCost,
+    /// Transaction job that carried the cancellation
+    pub job_id: i32,
 }
 
 /// Cancel a market on-chain (refund all bets)
 /// Called by the backend cron when market expires, or manually by admin
+#[utoipa::path(
+    post,
+    path = "/market/cancel",
+    security(("bearer" = [])),
+    tag = "oracle",
+    request_body = CancelMarketRequest,
+    params(DryRunParams),
+    responses(
+        (status = 200, description = "Cancellation submitted; a DryRunReport with `dry_run=true`", body = CancelMarketResponse),
+        (status = 202, description = "Cancellation sent but not yet confirmed; poll the job", body = tx_job::Model),
+        (status = 403, description = "Backend signer is not the oracle and the market has not expired (`not_authorized`), or caller lacks the required role", body = TxErrorBody),
+        (status = 409, description = "Market already resolved or cancelled, or another transaction is in flight", body = TxErrorBody),
+        (status = 422, description = "Transaction could not be built or failed on-chain", body = TxErrorBody),
+        (status = 500, description = "Backend misconfigured", body = TxErrorBody),
+        (status = 502, description = "Fullnode unreachable", body = TxErrorBody),
+        (status = 401, description = "Missing or invalid session", body = String),
+    )
+)]
 pub async fn cancel_market(
-    State(_db): State<DatabaseConnection>,
+    State(db): State<DatabaseConnection>,
+    Extension(session): Extension<AuthSession>,
+    Query(params): Query<DryRunParams>,
     Json(payload): Json<CancelMarketRequest>,
-) -> Result<impl IntoResponse, (StatusCode, String)> {
-    match execute_cancel_market(&payload.market_id).await {
-        Ok(digest) => Ok(Json(CancelMarketResponse {
-            digest,
-            status: "Success".to_string(), // Simplified status since we don't return effects here
-        })),
-        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e)),
+) -> Result<Response, TxError> {
+    // Same pre-flight checks as a real submit, so both report the same error
+    if params.enabled() {
+        let executor = Executor::from_env().await?;
+        preflight::check_cancel(&db, &executor, &payload.market_id).await?;
+        let report = executor.dry_run(cancel_call(&payload.market_id)?).await?;
+        return Ok(Json(report).into_response());
     }
-}
-
-pub async fn execute_cancel_market(market_id_str: &str) -> Result<String, String> {
-    // 1. Load config
-    let network = std::env::var("SUI_NETWORK")
-        .unwrap_or_else(|_| "https://fullnode.testnet.sui.io:443".to_string());
-    let package_id_str =
-        std::env::var("PACKAGE_ID").map_err(|_| "PACKAGE_ID not set".to_string())?;
-
-    // 2. Setup Client
-    let client = SuiClientBuilder::default()
-        .build(&network)
-        .await
-        .map_err(|e| format!("Failed to create Sui client: {}", e))?;
 
-    // 3. Load Keystore (Admin Wallet)
-    let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
-    let keystore_path = PathBuf::from(home).join(".sui/sui_config/sui.keystore");
-
-    let keystore = FileBasedKeystore::load_or_create(&keystore_path)
-        .map_err(|e| format!("Failed to load keystore: {}", e))?;
-
-    let addresses = keystore.addresses();
-    if addresses.is_empty() {
-        return Err("No accounts found in sui.keystore".to_string());
+    match execute_cancel_market(&db, session.address, &payload.market_id).await? {
+        JobRun::Confirmed { job, outcome } => Ok(Json(CancelMarketResponse {
+            digest: outcome.digest,
+            status: "Success".to_string(), // Failed executions come back as errors
+            gas_budget: outcome.gas_budget,
+            gas_used: outcome.gas,
+            job_id: job.id,
+        })
+        .into_response()),
+        JobRun::Pending(job) => Ok((StatusCode::ACCEPTED, Json(job)).into_response()),
     }
-    let sender = addresses[0];
-
-    println!("Cancel: Using Admin Account: {}", sender);
-
-    // 4. Prepare Arguments
-    let market_id =
-        ObjectID::from_str(market_id_str).map_err(|e| format!("Invalid Market ID: {}", e))?;
-    let package_id =
-        ObjectID::from_str(&package_id_str).map_err(|e| format!("Invalid Package ID: {}", e))?;
-
-    // 5. Build Transaction
-    let gas_price = client
-        .read_api()
-        .get_reference_gas_price()
-        .await
-        .unwrap_or(1000);
+}
 
-    let market_arg = SuiJsonValue::from_object_id(market_id);
+pub fn cancel_call(market_id_str: &str) -> Result<MoveCall, TxError> {
+    let market_id = ObjectID::from_str(market_id_str)
+        .map_err(|e| TxError::Build(format!("Invalid Market ID: {}", e)))?;
 
     // Clock object is at 0x6
     let clock_id = ObjectID::from_str("0x6").unwrap();
-    let clock_arg = SuiJsonValue::from_object_id(clock_id);
-
-    let tx_data = client
-        .transaction_builder()
-        .move_call(
-            sender,
-            package_id,
-            "market",
-            "cancel_market",
-            vec![], // type_args
-            vec![market_arg, clock_arg],
-            None,       // gas
-            50_000_000, // gas_budget
-            Some(gas_price),
-        )
-        .await
-        .map_err(|e| format!("Failed to build transaction data: {}", e))?;
 
-    // Sign using keystore
-    let signature = keystore
-        .sign_secure(&sender, &tx_data, Intent::sui_transaction())
-        .await
-        .map_err(|e| format!("Failed to sign transaction: {}", e))?;
-
-    // Execute
-    let response = client
-        .quorum_driver_api()
-        .execute_transaction_block(
-            Transaction::from_data(tx_data, vec![signature]),
-            SuiTransactionBlockResponseOptions::new()
-                .with_effects()
-                .with_events(),
-            None,
-        )
-        .await
-        .map_err(|e| format!("Failed to execute transaction: {}", e))?;
+    Ok(MoveCall::new(
+        "cancel_market",
+        vec![
+            SuiJsonValue::from_object_id(market_id),
+            SuiJsonValue::from_object_id(clock_id),
+        ],
+    ))
+}
 
-    Ok(response.digest.to_string())
+pub async fn execute_cancel_market(
+    db: &DatabaseConnection,
+    actor: impl ToString,
+    market_id_str: &str,
+) -> Result<JobRun, TxError> {
+    let spec = JobSpec::CancelMarket {
+        market_id: market_id_str.to_string(),
+    };
+    let checked = match Executor::from_env().await {
+        Ok(executor) => preflight::check_cancel(db, &executor, market_id_str).await,
+        Err(e) => Err(e),
+    };
+    if let Err(e) = checked {
+        return Err(jobs::reject(db, actor, &spec, e).await);
+    }
+    jobs::run(db, actor, spec).await
 }
@@ -8,6 +8,15 @@ use sea_orm::{
 };
 use crate::entities::category;
 
+#[utoipa::path(
+    get,
+    path = "/categories",
+    tag = "categories",
+    responses(
+        (status = 200, description = "All categories", body = [category::Model]),
+        (status = 500, description = "Database error", body = String),
+    )
+)]
 pub async fn list_categories(
     State(db): State<DatabaseConnection>,
 ) -> Result<Json<Vec<category::Model>>, (StatusCode, String)> {
@@ -4,6 +4,14 @@ use axum::{
 };
 use std::env;
 
+#[utoipa::path(
+    get,
+    path = "/config.js",
+    tag = "config",
+    responses(
+        (status = 200, description = "Frontend runtime config script", content_type = "application/javascript", body = String),
+    )
+)]
 pub async fn get_config() -> impl IntoResponse {
     let package_id = env::var("VITE_PACKAGE_ID").unwrap_or_default();
     let sui_network = env::var("VITE_SUI_NETWORK").unwrap_or_default();
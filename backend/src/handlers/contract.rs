@@ -1,46 +1,77 @@
-use crate::entities::contract;
+use crate::audit::{self, AuditEntry};
+use crate::auth::AuthSession;
+use crate::chain::{self, MarketCreatedEvent, MarketLookupError};
+use crate::entities::tx_job::{self, JobStatus};
+use crate::entities::{
+    category, contract, favorite, fee_withdrawal, market_history, market_resolution,
+    market_resolver, pending_resolution,
+};
+use crate::idempotency;
+use crate::jobs::{self, JobRun, JobSpec};
+use crate::tx::{self, DryRunParams, DryRunReport, Executor, MoveCall, TxError};
+use crate::validation::{self, ValidationErrors};
 use axum::{
-    extract::{Path, Query, State},
-    http::StatusCode,
+    extract::{Extension, Path, Query, State},
+    http::{HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
     Json,
 };
-use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
-use serde::Deserialize;
-use shared_crypto::intent::Intent;
-use std::path::PathBuf;
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter,
+    QueryOrder, Set, SqlErr, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
 use std::str::FromStr;
-use sui_keys::keystore::{AccountKeystore, FileBasedKeystore};
 use sui_sdk::{
-    rpc_types::{SuiExecutionStatus, SuiTransactionBlockResponseOptions},
-    types::{
-        base_types::ObjectID,
-        transaction::{CallArg, Transaction, TransactionData},
-    },
+    json::SuiJsonValue,
+    types::base_types::{ObjectID, SuiAddress},
     SuiClientBuilder,
 };
+use utoipa::{IntoParams, ToSchema};
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, ToSchema)]
 pub struct CreateContract {
     pub name: String,
+    /// Existing market object to import; its outcome count, end date, oracle and fee are read from chain
     pub address: Option<String>,
     pub description: Option<String>,
     pub options: Option<Vec<String>>,
     pub category_id: Option<i32>,
     pub end_date: Option<String>,
+    /// Address allowed to resolve the market; defaults to the backend signer
+    pub oracle: Option<String>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct ListContractParams {
     pub category_id: Option<i32>,
     pub q: Option<String>,
+    /// Include archived markets (default false)
+    pub include_archived: Option<bool>,
 }
 
+#[utoipa::path(
+    get,
+    path = "/contracts",
+    tag = "contracts",
+    params(ListContractParams),
+    responses(
+        (status = 200, description = "Registered markets", body = [contract::Model]),
+        (status = 500, description = "Database error", body = String),
+    )
+)]
 pub async fn list_contracts(
     State(db): State<DatabaseConnection>,
     Query(params): Query<ListContractParams>,
 ) -> Result<Json<Vec<contract::Model>>, (StatusCode, String)> {
     let mut query = contract::Entity::find();
 
+    if !params.include_archived.unwrap_or(false) {
+        query = query.filter(contract::Column::ArchivedAt.is_null());
+    }
+
     if let Some(cat_id) = params.category_id {
         query = query.filter(contract::Column::CategoryId.eq(cat_id));
     }
@@ -62,226 +93,807 @@ pub async fn list_contracts(
     Ok(Json(contracts))
 }
 
+#[derive(Serialize, ToSchema)]
+pub struct MarketDetail {
+    #[serde(flatten)]
+    pub contract: contract::Model,
+    /// How and when the market was resolved; absent until an oracle resolution confirms
+    pub resolution: Option<market_resolution::Model>,
+}
+
+/// A single market with its resolution record
+#[utoipa::path(
+    get,
+    path = "/contracts/{id}",
+    tag = "contracts",
+    params(("id" = i32, Path, description = "Contract id")),
+    responses(
+        (status = 200, description = "Market detail", body = MarketDetail),
+        (status = 404, description = "Contract not found", body = String),
+        (status = 500, description = "Database error", body = String),
+    )
+)]
+pub async fn get_contract(
+    State(db): State<DatabaseConnection>,
+    Path(id): Path<i32>,
+) -> Result<Json<MarketDetail>, (StatusCode, String)> {
+    let contract = contract::Entity::find_by_id(id)
+        .one(&db)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, "Contract not found".to_string()))?;
+
+    let resolution = market_resolution::Entity::find()
+        .filter(market_resolution::Column::MarketAddress.eq(&contract.address))
+        .order_by_desc(market_resolution::Column::Id)
+        .one(&db)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(MarketDetail {
+        contract,
+        resolution,
+    }))
+}
+
+/// Import an existing on-chain market, or create a new one when no address is given
+#[utoipa::path(
+    post,
+    path = "/contracts",
+    security(("bearer" = [])),
+    tag = "contracts",
+    request_body = CreateContract,
+    params(
+        DryRunParams,
+        ("Idempotency-Key" = Option<String>, Header, description = "Client-chosen key; repeating the same request from the same wallet returns the first result"),
+    ),
+    responses(
+        (status = 200, description = "Stored market; a DryRunReport with `dry_run=true`", body = contract::Model,
+            headers(
+                ("x-gas-budget" = u64, description = "Gas budget of the creation transaction (MIST)"),
+                ("x-gas-used" = i64, description = "Net gas charged for the creation transaction (MIST)"),
+            )),
+        (status = 202, description = "Creation sent but not yet confirmed; poll the job", body = tx_job::Model),
+        (status = 400, description = "Invalid fields", body = ValidationErrors),
+        (status = 409, description = "A request with the same Idempotency-Key is in progress, or the imported market was registered concurrently", body = String),
+        (status = 422, description = "On-chain creation could not be built or aborted, or the Idempotency-Key was used for a different request", body = String),
+        (status = 500, description = "Backend misconfigured or database error", body = String),
+        (status = 502, description = "Fullnode unreachable", body = String),
+        (status = 401, description = "Missing or invalid session", body = String),
+        (status = 403, description = "Caller lacks the required role", body = String),
+    )
+)]
 pub async fn create_contract(
     State(db): State<DatabaseConnection>,
-    axum::Extension(tx): axum::Extension<tokio::sync::mpsc::Sender<()>>,
+    Extension(session): Extension<AuthSession>,
+    Extension(tx): Extension<tokio::sync::mpsc::Sender<()>>,
+    Query(params): Query<DryRunParams>,
+    headers: HeaderMap,
     Json(payload): Json<CreateContract>,
-) -> Result<Json<contract::Model>, (StatusCode, String)> {
-    // 1. Determine the address (Import or Create)
-    let contract_address = if let Some(addr) = payload.address.filter(|a| !a.trim().is_empty()) {
-        addr
+) -> Result<Response, Response> {
+    // A repeated key returns what the first request produced; dry runs ignore it
+    let idempotency_key = if params.enabled() {
+        None
     } else {
-        // 2. Perform On-Chain Creation
-        create_market_on_chain(
-            &payload.name,
-            payload.options.as_ref().map(|v| v.len()).unwrap_or(2) as u8,
-            payload.end_date.as_deref(),
+        idempotency::from_headers(&headers).map_err(IntoResponse::into_response)?
+    };
+    let _claim = match &idempotency_key {
+        Some(key) => {
+            let request_hash = idempotency::request_hash(&payload);
+            let claim = idempotency::claim(&db, key, &session.address.to_string(), &request_hash)
+                .await
+                .map_err(IntoResponse::into_response)?;
+            if let Some(response) = replay(&db, key).await? {
+                return Ok(response);
+            }
+            Some(claim)
+        }
+        None => None,
+    };
+
+    // Reject bad input before any on-chain transaction is attempted
+    let mut market = validate_new_market(&db, &payload)
+        .await
+        .map_err(IntoResponse::into_response)?;
+
+    if params.enabled() {
+        if market.import_address.is_some() {
+            let mut errors = ValidationErrors::new();
+            errors.add(
+                "address",
+                "dry_run only applies to creating a market on chain",
+            );
+            return Err(errors.into_response());
+        }
+        return dry_run_create(&market)
+            .await
+            .map(|report| Json(report).into_response())
+            .map_err(|e| <(StatusCode, String)>::from(e).into_response());
+    }
+
+    if let Some(market_id) = market.import_address {
+        verify_import(&db, &mut market, market_id).await?;
+    }
+
+    let listing = market.listing(idempotency_key);
+
+    if let Some(market_id) = market.import_address {
+        let inserted = insert_listing(
+            &db,
+            listing,
+            market_id.to_string(),
+            market.oracle.map(|o| o.to_string()),
         )
         .await
-        .map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("On-chain creation failed: {}", e),
-            )
-        })?
-    };
+        .map_err(|e| match e.sql_err() {
+            // Imported concurrently by another request
+            Some(SqlErr::UniqueConstraintViolation(_)) => (
+                StatusCode::CONFLICT,
+                format!("Market {} is already registered", market_id),
+            ),
+            _ => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+        });
+
+        audit::record(
+            &db,
+            AuditEntry::new(session.address, "import_market")
+                .payload(&payload)
+                .contract(inserted.as_ref().ok().map(|c| c.id))
+                .market(market_id)
+                .error(inserted.as_ref().err().map(|(_, e)| e.clone())),
+        )
+        .await;
 
-    let options_json = payload
-        .options
-        .map(|opts| serde_json::to_string(&opts).unwrap_or("[]".to_string()));
+        let contract = inserted.map_err(IntoResponse::into_response)?;
 
-    let new_contract = contract::ActiveModel {
-        name: Set(payload.name),
-        address: Set(contract_address),
-        description: Set(payload.description),
-        options: Set(options_json),
-        category_id: Set(payload.category_id),
-        end_date: Set(payload.end_date),
-        resolved: Set(false),
-        cancelled: Set(false),
-        ..Default::default()
+        // Trigger instant indexer refresh
+        let _ = tx.send(()).await;
+
+        return Ok(Json(contract).into_response());
+    }
+
+    // The job stores the listing once the creation transaction confirms
+    let spec = JobSpec::CreateMarket {
+        question: market.name.clone(),
+        options_count: market.options_count,
+        end_time_ms: market.end_time_ms,
+        oracle: market.oracle.map(|o| o.to_string()),
+        listing,
+    };
+    let (job, outcome) = match jobs::run(&db, session.address, spec).await {
+        Ok(JobRun::Confirmed { job, outcome }) => (job, outcome),
+        Ok(JobRun::Pending(job)) => {
+            return Ok((StatusCode::ACCEPTED, Json(job)).into_response());
+        }
+        Err(e) => {
+            let message = format!("On-chain creation failed: {}", e);
+            return Err((e.status_code(), message).into_response());
+        }
     };
 
-    let contract = new_contract
-        .insert(&db)
-        .await
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let stored = match job.contract_id {
+        Some(id) => contract::Entity::find_by_id(id)
+            .one(&db)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response())?,
+        None => None,
+    };
+    let contract = stored.ok_or_else(|| {
+        let message = job
+            .last_error
+            .clone()
+            .unwrap_or_else(|| format!("Market created in {} but not stored", outcome.digest));
+        (StatusCode::INTERNAL_SERVER_ERROR, message).into_response()
+    })?;
 
     // Trigger instant indexer refresh
     let _ = tx.send(()).await;
 
-    Ok(Json(contract))
+    Ok(with_gas_headers(
+        Json(contract).into_response(),
+        outcome.gas_budget,
+        outcome.gas.net,
+    ))
 }
 
+fn with_gas_headers(mut response: Response, gas_budget: u64, gas_used: i64) -> Response {
+    let headers = response.headers_mut();
+    headers.insert(tx::GAS_BUDGET_HEADER, HeaderValue::from(gas_budget));
+    headers.insert(tx::GAS_USED_HEADER, HeaderValue::from(gas_used));
+    response
+}
+
+/// Response for an earlier request with the same Idempotency-Key, if it got anywhere.
+/// Failed jobs changed nothing on chain, so their keys may be retried.
+async fn replay(db: &DatabaseConnection, key: &str) -> Result<Option<Response>, Response> {
+    let db_error =
+        |e: sea_orm::DbErr| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response();
+
+    let stored = contract::Entity::find()
+        .filter(contract::Column::IdempotencyKey.eq(key))
+        .one(db)
+        .await
+        .map_err(db_error)?;
+    let job = tx_job::Entity::find()
+        .filter(tx_job::Column::IdempotencyKey.eq(key))
+        .filter(tx_job::Column::Status.ne(JobStatus::Failed))
+        .order_by_desc(tx_job::Column::Id)
+        .one(db)
+        .await
+        .map_err(db_error)?;
+
+    let response = match (stored, job) {
+        (Some(contract), job) => {
+            let response = Json(contract).into_response();
+            let result = job
+                .and_then(|j| j.result)
+                .and_then(|r| serde_json::from_str::<serde_json::Value>(&r).ok());
+            let gas = result.as_ref().and_then(|r| {
+                Some((
+                    r.get("gas_budget")?.as_u64()?,
+                    r.get("gas_used")?.get("net")?.as_i64()?,
+                ))
+            });
+            match gas {
+                Some((budget, used)) => with_gas_headers(response, budget, used),
+                None => response,
+            }
+        }
+        (None, Some(job)) if job.status == JobStatus::Confirmed => {
+            let message = job
+                .last_error
+                .unwrap_or_else(|| format!("Job {} confirmed but no market was stored", job.id));
+            (StatusCode::INTERNAL_SERVER_ERROR, message).into_response()
+        }
+        (None, Some(job)) => (StatusCode::ACCEPTED, Json(job)).into_response(),
+        (None, None) => return Ok(None),
+    };
+    Ok(Some(response))
+}
+
+/// Permanently remove a market with its price history, favorites and resolvers.
+/// Jobs, resolutions and fee withdrawals describe on-chain transactions and are
+/// kept, detached from the market. The on-chain market is untouched; archive
+/// instead to keep indexing it.
+#[utoipa::path(
+    delete,
+    path = "/contracts/{id}",
+    security(("bearer" = [])),
+    tag = "contracts",
+    params(("id" = i32, Path, description = "Contract id")),
+    responses(
+        (status = 204, description = "Deleted"),
+        (status = 404, description = "Contract not found", body = String),
+        (status = 401, description = "Missing or invalid session", body = String),
+        (status = 403, description = "Caller lacks the required role", body = String),
+    )
+)]
 pub async fn delete_contract(
     State(db): State<DatabaseConnection>,
+    Extension(session): Extension<AuthSession>,
     Path(id): Path<i32>,
 ) -> Result<StatusCode, (StatusCode, String)> {
-    let result = contract::Entity::delete_by_id(id)
-        .exec(&db)
+    let existing = contract::Entity::find_by_id(id)
+        .one(&db)
         .await
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, "Contract not found".to_string()))?;
+
+    // SQLite does not enforce our foreign keys, so remove dependents explicitly
+    let result = db
+        .transaction::<_, u64, sea_orm::DbErr>(|txn| {
+            Box::pin(async move {
+                market_history::Entity::delete_many()
+                    .filter(market_history::Column::ContractId.eq(id))
+                    .exec(txn)
+                    .await?;
+                favorite::Entity::delete_many()
+                    .filter(favorite::Column::ContractId.eq(id))
+                    .exec(txn)
+                    .await?;
+                // Otherwise the resolver cron would still resolve the market
+                market_resolver::Entity::delete_many()
+                    .filter(market_resolver::Column::ContractId.eq(id))
+                    .exec(txn)
+                    .await?;
+                tx_job::Entity::update_many()
+                    .col_expr(tx_job::Column::ContractId, Expr::value(None::<i32>))
+                    .filter(tx_job::Column::ContractId.eq(id))
+                    .exec(txn)
+                    .await?;
+                market_resolution::Entity::update_many()
+                    .col_expr(
+                        market_resolution::Column::ContractId,
+                        Expr::value(None::<i32>),
+                    )
+                    .filter(market_resolution::Column::ContractId.eq(id))
+                    .exec(txn)
+                    .await?;
+                pending_resolution::Entity::update_many()
+                    .col_expr(
+                        pending_resolution::Column::ContractId,
+                        Expr::value(None::<i32>),
+                    )
+                    .filter(pending_resolution::Column::ContractId.eq(id))
+                    .exec(txn)
+                    .await?;
+                fee_withdrawal::Entity::update_many()
+                    .col_expr(fee_withdrawal::Column::ContractId, Expr::value(None::<i32>))
+                    .filter(fee_withdrawal::Column::ContractId.eq(id))
+                    .exec(txn)
+                    .await?;
+                let deleted = contract::Entity::delete_by_id(id).exec(txn).await?;
+                Ok(deleted.rows_affected)
+            })
+        })
+        .await
+        .map_err(|e| e.to_string());
+
+    audit::record(
+        &db,
+        AuditEntry::new(session.address, "delete_market")
+            .contract(Some(id))
+            .market(&existing.address)
+            .error(result.as_ref().err().cloned()),
+    )
+    .await;
+
+    let rows_affected = result.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
 
-    if result.rows_affected == 0 {
+    if rows_affected == 0 {
         return Err((StatusCode::NOT_FOUND, "Contract not found".to_string()));
     }
 
     Ok(StatusCode::NO_CONTENT)
 }
 
-// --- Helper Functions ---
+/// Hide a market from listings; it is still indexed and can be resolved or cancelled
+#[utoipa::path(
+    post,
+    path = "/contracts/{id}/archive",
+    security(("bearer" = [])),
+    tag = "contracts",
+    params(("id" = i32, Path, description = "Contract id")),
+    responses(
+        (status = 200, description = "Archived market", body = contract::Model),
+        (status = 404, description = "Contract not found", body = String),
+        (status = 409, description = "Already archived", body = String),
+        (status = 401, description = "Missing or invalid session", body = String),
+        (status = 403, description = "Caller lacks the required role", body = String),
+    )
+)]
+pub async fn archive_contract(
+    State(db): State<DatabaseConnection>,
+    Extension(session): Extension<AuthSession>,
+    Path(id): Path<i32>,
+) -> Result<Json<contract::Model>, (StatusCode, String)> {
+    set_archived(&db, session, id, true).await.map(Json)
+}
+
+/// Undo an archive so the market is listed again
+#[utoipa::path(
+    post,
+    path = "/contracts/{id}/restore",
+    security(("bearer" = [])),
+    tag = "contracts",
+    params(("id" = i32, Path, description = "Contract id")),
+    responses(
+        (status = 200, description = "Restored market", body = contract::Model),
+        (status = 404, description = "Contract not found", body = String),
+        (status = 409, description = "Not archived", body = String),
+        (status = 401, description = "Missing or invalid session", body = String),
+        (status = 403, description = "Caller lacks the required role", body = String),
+    )
+)]
+pub async fn restore_contract(
+    State(db): State<DatabaseConnection>,
+    Extension(session): Extension<AuthSession>,
+    Path(id): Path<i32>,
+) -> Result<Json<contract::Model>, (StatusCode, String)> {
+    set_archived(&db, session, id, false).await.map(Json)
+}
+
+async fn set_archived(
+    db: &DatabaseConnection,
+    session: AuthSession,
+    id: i32,
+    archive: bool,
+) -> Result<contract::Model, (StatusCode, String)> {
+    let existing = contract::Entity::find_by_id(id)
+        .one(db)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, "Contract not found".to_string()))?;
+
+    if existing.archived_at.is_some() == archive {
+        let state = if archive { "already" } else { "not" };
+        return Err((
+            StatusCode::CONFLICT,
+            format!("Contract is {} archived", state),
+        ));
+    }
 
-const SUI_NETWORK_URL: &str = "https://fullnode.testnet.sui.io:443";
+    let market_address = existing.address.clone();
+    let mut active: contract::ActiveModel = existing.into();
+    active.archived_at = Set(archive.then(|| chrono::Utc::now().to_rfc3339()));
+    let result = active.update(db).await.map_err(|e| e.to_string());
 
-async fn create_market_on_chain(
-    question: &str,
+    let action = if archive {
+        "archive_market"
+    } else {
+        "restore_market"
+    };
+    audit::record(
+        db,
+        AuditEntry::new(session.address, action)
+            .contract(Some(id))
+            .market(&market_address)
+            .error(result.as_ref().err().cloned()),
+    )
+    .await;
+
+    result.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))
+}
+
+// --- Helper Functions ---
+
+const MAX_NAME_LEN: usize = 200;
+const MAX_DESCRIPTION_LEN: usize = 2000;
+const MAX_OPTION_LEN: usize = 100;
+// options_count is a u8 on-chain and the contract requires at least two outcomes
+const MIN_OPTIONS: usize = 2;
+const MAX_OPTIONS: usize = u8::MAX as usize;
+
+/// `CreateContract` after validation and normalisation
+struct NewMarket {
+    name: String,
+    description: Option<String>,
+    options: Option<Vec<String>>, // None = default binary Yes/No market
     options_count: u8,
-    end_date: Option<&str>,
-) -> Result<String, Box<dyn std::error::Error>> {
-    println!(
-        "Creating market on chain... Question: {}, Options: {}, EndDate: {:?}",
-        question, options_count, end_date
-    );
-
-    // 1. Setup Client
-    let sui_client = SuiClientBuilder::default().build(SUI_NETWORK_URL).await?;
-
-    // 2. Load Keystore (Admin Wallet)
-    let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
-    let keystore_path = PathBuf::from(home).join(".sui/sui_config/sui.keystore");
-
-    let keystore = FileBasedKeystore::load_or_create(&keystore_path)?;
-    let addresses = keystore.addresses();
-    if addresses.is_empty() {
-        return Err("No accounts found in sui.keystore".into());
+    category_id: Option<i32>,
+    end_date: Option<String>,
+    end_time_ms: u64, // 0 = no expiry
+    oracle: Option<SuiAddress>,
+    import_address: Option<ObjectID>,
+    platform_fee_bps: Option<i32>,
+}
+
+impl NewMarket {
+    fn listing(&self, idempotency_key: Option<String>) -> NewListing {
+        NewListing {
+            name: self.name.clone(),
+            description: self.description.clone(),
+            options: self.options.clone(),
+            category_id: self.category_id,
+            end_date: self.end_date.clone(),
+            platform_fee_bps: self.platform_fee_bps,
+            idempotency_key,
+        }
     }
-    let sender = addresses[0];
-    // let _keypair = keystore.get_key(&sender)?;
+}
 
-    println!("Using Admin Account: {}", sender);
+/// Off-chain fields of a market, stored once its object id is known
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct NewListing {
+    pub name: String,
+    pub description: Option<String>,
+    pub options: Option<Vec<String>>,
+    pub category_id: Option<i32>,
+    pub end_date: Option<String>,
+    pub platform_fee_bps: Option<i32>,
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
+}
 
-    // 3. Prepare Arguments
-    // PACKAGE_ID must be set via environment variable (set by `just dev-run`)
-    let env_package_id = std::env::var("PACKAGE_ID").map_err(|_| {
-        "PACKAGE_ID environment variable not set. Run with `just dev-run` to set it automatically."
-    })?;
-    println!("Using Package ID: {}", env_package_id);
+pub async fn insert_listing(
+    db: &DatabaseConnection,
+    listing: NewListing,
+    address: String,
+    oracle_address: Option<String>,
+) -> Result<contract::Model, sea_orm::DbErr> {
+    let options_json = listing
+        .options
+        .map(|opts| serde_json::to_string(&opts).unwrap_or("[]".to_string()));
 
-    let package_id = ObjectID::from_str(&env_package_id)?;
-    let module = "market";
-    let function = "create_market";
+    contract::ActiveModel {
+        name: Set(listing.name),
+        address: Set(address),
+        description: Set(listing.description),
+        options: Set(options_json),
+        category_id: Set(listing.category_id),
+        end_date: Set(listing.end_date),
+        resolved: Set(false),
+        cancelled: Set(false),
+        oracle_address: Set(oracle_address),
+        platform_fee_bps: Set(listing.platform_fee_bps),
+        idempotency_key: Set(listing.idempotency_key),
+        ..Default::default()
+    }
+    .insert(db)
+    .await
+}
 
-    let pure_question = bcs::to_bytes(&question.as_bytes().to_vec())?;
-    let pure_options_count = bcs::to_bytes(&options_count)?;
-    let pure_oracle = bcs::to_bytes(&sender)?;
-    let platform_fee_bps: u16 = 200; // 2% platform fee
-    let pure_platform_fee_bps = bcs::to_bytes(&platform_fee_bps)?;
+/// Insert the listing of a market created by a job, or return the row an
+/// earlier confirmation of the same market stored
+pub async fn store_listing(
+    db: &DatabaseConnection,
+    listing: NewListing,
+    created: &MarketCreatedEvent,
+) -> Result<contract::Model, sea_orm::DbErr> {
+    let inserted = insert_listing(
+        db,
+        listing,
+        created.id.clone(),
+        Some(created.oracle.clone()),
+    )
+    .await;
+    match inserted {
+        Err(e) if matches!(e.sql_err(), Some(SqlErr::UniqueConstraintViolation(_))) => {
+            contract::Entity::find()
+                .filter(contract::Column::Address.eq(&created.id))
+                .one(db)
+                .await?
+                .ok_or(e)
+        }
+        result => result,
+    }
+}
 
-    // Load platform admin address from environment
-    let platform_admin_str =
-        std::env::var("PLATFORM_ADMIN_ADDRESS").unwrap_or_else(|_| sender.to_string()); // Fallback to sender if not set
-    let platform_admin = sui_sdk::types::base_types::SuiAddress::from_str(&platform_admin_str)
-        .map_err(|e| format!("Invalid PLATFORM_ADMIN_ADDRESS: {}", e))?;
-    let pure_platform_admin = bcs::to_bytes(&platform_admin)?;
-
-    // Convert end_date ISO string to milliseconds timestamp
-    let end_time_ms: u64 = if let Some(date_str) = end_date {
-        chrono::DateTime::parse_from_rfc3339(date_str)
-            .or_else(|_| {
-                // Try parsing as date only
-                chrono::NaiveDate::parse_from_str(date_str, "%Y-%m-%d")
-                    .map(|d| d.and_hms_opt(23, 59, 59).unwrap().and_utc().fixed_offset())
-            })
-            .map(|dt| dt.timestamp_millis() as u64)
-            .unwrap_or(0)
-    } else {
-        0 // No expiration
+async fn validate_new_market(
+    db: &DatabaseConnection,
+    payload: &CreateContract,
+) -> Result<NewMarket, ValidationErrors> {
+    let mut errors = ValidationErrors::new();
+
+    let name = payload.name.trim().to_string();
+    if name.is_empty() {
+        errors.add("name", "must not be empty");
+    } else if name.chars().count() > MAX_NAME_LEN {
+        errors.add(
+            "name",
+            format!("must be at most {} characters", MAX_NAME_LEN),
+        );
+    }
+
+    let description = payload
+        .description
+        .as_deref()
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+        .map(str::to_string);
+    if description
+        .as_ref()
+        .is_some_and(|d| d.chars().count() > MAX_DESCRIPTION_LEN)
+    {
+        errors.add(
+            "description",
+            format!("must be at most {} characters", MAX_DESCRIPTION_LEN),
+        );
+    }
+
+    let options: Option<Vec<String>> = payload
+        .options
+        .as_ref()
+        .map(|opts| opts.iter().map(|o| o.trim().to_string()).collect());
+    let options_count = options.as_ref().map(|o| o.len()).unwrap_or(MIN_OPTIONS);
+    if let Some(opts) = &options {
+        if opts.len() < MIN_OPTIONS || opts.len() > MAX_OPTIONS {
+            errors.add(
+                "options",
+                format!(
+                    "must have between {} and {} outcomes, got {}",
+                    MIN_OPTIONS,
+                    MAX_OPTIONS,
+                    opts.len()
+                ),
+            );
+        }
+        let mut seen = std::collections::HashSet::new();
+        for (i, label) in opts.iter().enumerate() {
+            let field = format!("options[{}]", i);
+            if label.is_empty() {
+                errors.add(field, "must not be empty");
+            } else if label.chars().count() > MAX_OPTION_LEN {
+                errors.add(
+                    field,
+                    format!("must be at most {} characters", MAX_OPTION_LEN),
+                );
+            } else if !seen.insert(label.to_lowercase()) {
+                errors.add(field, format!("duplicate outcome label {:?}", label));
+            }
+        }
+    }
+
+    let import_address = match payload.address.as_deref().map(str::trim) {
+        Some(a) if !a.is_empty() => match ObjectID::from_str(a) {
+            Ok(id) => Some(id),
+            Err(_) => {
+                errors.add("address", "must be a valid object id");
+                None
+            }
+        },
+        _ => None,
     };
-    let pure_end_time_ms = bcs::to_bytes(&end_time_ms)?;
-
-    // 4. Construct Transaction
-    // Get gas object (Pick first available coin with enough balance)
-    let coins = sui_client
-        .coin_read_api()
-        .get_coins(sender, None, None, None)
-        .await?;
-
-    // Simple gas selection: just take the first one.
-    // In production, we should merge coins or pick one with > budget.
-    let coin = coins.data.into_iter().next().ok_or("No gas coins found")?;
-    let gas_payment = coin.coin_object_id;
-    let gas_budget = 50_000_000;
-    let gas_price = sui_client.read_api().get_reference_gas_price().await?;
-
-    // Get the gas object ref
-    let pt_response = sui_client
-        .read_api()
-        .get_object_with_options(
-            gas_payment,
-            sui_sdk::rpc_types::SuiObjectDataOptions::new().with_owner(),
-        )
-        .await?;
-
-    let gas_obj_ref = pt_response.into_object()?.object_ref();
-
-    let tx_data = TransactionData::new_move_call(
-        sender,
-        package_id,
-        sui_sdk::types::Identifier::from_str(module)?,
-        sui_sdk::types::Identifier::from_str(function)?,
-        vec![],
-        gas_obj_ref,
-        vec![
-            CallArg::Pure(pure_question),
-            CallArg::Pure(pure_options_count),
-            CallArg::Pure(pure_oracle),
-            CallArg::Pure(pure_platform_fee_bps),
-            CallArg::Pure(pure_platform_admin),
-            CallArg::Pure(pure_end_time_ms),
-        ],
-        gas_budget,
-        gas_price,
-    )?;
 
-    // 5. Sign and Execute
-    let signature = keystore
-        .sign_secure(&sender, &tx_data, Intent::sui_transaction())
-        .await?;
-    let transaction = Transaction::from_data(tx_data, vec![signature]);
-
-    let response = sui_client
-        .quorum_driver_api()
-        .execute_transaction_block(
-            transaction,
-            SuiTransactionBlockResponseOptions::new()
-                .with_effects()
-                .with_events(),
-            None,
-        )
-        .await?;
+    let end_date = payload
+        .end_date
+        .as_deref()
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+        .map(str::to_string);
+    let mut end_time_ms = 0;
+    // Imported markets take their end date from chain, see `verify_import`
+    if let (Some(date_str), None) = (&end_date, import_address) {
+        match validation::parse_end_date(date_str) {
+            Some(dt) if dt <= chrono::Utc::now() => {
+                errors.add("end_date", "must be in the future");
+            }
+            Some(dt) => end_time_ms = dt.timestamp_millis() as u64,
+            None => errors.add("end_date", "must be RFC 3339 or YYYY-MM-DD"),
+        }
+    }
 
-    if let Some(effects) = response.effects {
-        // Check status manually since trait might be missing or complex
-        let status = match &effects {
-            sui_sdk::rpc_types::SuiTransactionBlockEffects::V1(v1) => v1.status.clone(),
+    let oracle = match payload.oracle.as_deref().map(str::trim) {
+        Some(o) if !o.is_empty() => match SuiAddress::from_str(o) {
+            Ok(addr) => Some(addr),
+            Err(_) => {
+                errors.add("oracle", "must be a valid Sui address");
+                None
+            }
+        },
+        _ => None,
+    };
+
+    if let Some(category_id) = payload.category_id {
+        match category::Entity::find_by_id(category_id).one(db).await {
+            Ok(Some(_)) => {}
+            Ok(None) => errors.add("category_id", "unknown category"),
+            Err(e) => errors.add("category_id", format!("lookup failed: {}", e)),
+        }
+    }
+
+    errors.into_result(NewMarket {
+        name,
+        description,
+        options,
+        options_count: u8::try_from(options_count).unwrap_or(u8::MAX),
+        category_id: payload.category_id,
+        end_date,
+        end_time_ms,
+        oracle,
+        import_address,
+        // Overwritten from chain for imports
+        platform_fee_bps: Some(PLATFORM_FEE_BPS as i32),
+    })
+}
+
+/// Check an import against the on-chain object and fill in the fields the chain owns
+async fn verify_import(
+    db: &DatabaseConnection,
+    market: &mut NewMarket,
+    market_id: ObjectID,
+) -> Result<(), Response> {
+    let mut errors = ValidationErrors::new();
+
+    let existing = contract::Entity::find()
+        .filter(contract::Column::Address.eq(market_id.to_string()))
+        .one(db)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response())?;
+    if let Some(dup) = existing {
+        let message = match dup.archived_at {
+            Some(_) => format!(
+                "already registered as archived contract {}; restore it instead",
+                dup.id
+            ),
+            None => format!("already registered as contract {}", dup.id),
         };
-        if matches!(status, SuiExecutionStatus::Failure { .. }) {
-            return Err(format!("Transaction failed: {:?}", status).into());
+        errors.add("address", message);
+        return Err(errors.into_response());
+    }
+
+    let package_id =
+        chain::package_id().map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e).into_response())?;
+    let client = SuiClientBuilder::default()
+        .build(chain::sui_network())
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::BAD_GATEWAY,
+                format!("Failed to connect to Sui: {}", e),
+            )
+                .into_response()
+        })?;
+
+    let on_chain = match chain::fetch_market(&client, package_id, market_id).await {
+        Ok(m) => m,
+        Err(MarketLookupError::Rpc(e)) => {
+            return Err((StatusCode::BAD_GATEWAY, e).into_response());
+        }
+        Err(MarketLookupError::Invalid(e)) => {
+            errors.add("address", e);
+            return Err(errors.into_response());
         }
+    };
 
-        if let Some(events) = response.events {
-            for event in events.data {
-                if event.type_.name.as_str().contains("MarketCreated") {
-                    let json_val = event.parsed_json;
-                    if let Some(id_val) = json_val.get("id") {
-                        let id_str = id_val.as_str().unwrap_or_default().to_string();
-                        println!("Found Market ID from Event: {}", id_str);
-                        return Ok(id_str);
-                    }
-                }
-            }
+    if on_chain.options_count != market.options_count {
+        let message = match &market.options {
+            Some(_) => format!(
+                "market has {} outcomes on chain, got {} labels",
+                on_chain.options_count, market.options_count
+            ),
+            None => format!(
+                "market has {} outcomes on chain; supply {} labels",
+                on_chain.options_count, on_chain.options_count
+            ),
+        };
+        errors.add("options", message);
+    }
+    if let Some(oracle) = market.oracle {
+        if oracle != on_chain.oracle {
+            errors.add(
+                "oracle",
+                format!("does not match on-chain oracle {}", on_chain.oracle),
+            );
         }
     }
+    if !errors.is_empty() {
+        return Err(errors.into_response());
+    }
+
+    market.oracle = Some(on_chain.oracle);
+    market.platform_fee_bps = Some(on_chain.platform_fee_bps as i32);
+    market.end_time_ms = on_chain.end_time_ms.unwrap_or(0);
+    market.end_date = on_chain
+        .end_time_ms
+        .and_then(|ms| chrono::DateTime::from_timestamp_millis(ms as i64))
+        .map(|dt| dt.to_rfc3339());
+
+    Ok(())
+}
 
-    Err("Failed to retrieve created Market ID".into())
+const PLATFORM_FEE_BPS: u16 = 200; // 2% platform fee on markets created here
+
+/// `market::create_market` arguments, with the oracle defaulting to `sender`
+pub fn create_market_call(
+    sender: SuiAddress,
+    question: &str,
+    options_count: u8,
+    end_time_ms: u64,
+    oracle: Option<SuiAddress>,
+) -> Result<(MoveCall, SuiAddress), TxError> {
+    let oracle = oracle.unwrap_or(sender);
+
+    // Load platform admin address from environment
+    let platform_admin_str =
+        std::env::var("PLATFORM_ADMIN_ADDRESS").unwrap_or_else(|_| sender.to_string()); // Fallback to sender if not set
+    let platform_admin = SuiAddress::from_str(&platform_admin_str)
+        .map_err(|e| TxError::Config(format!("Invalid PLATFORM_ADMIN_ADDRESS: {}", e)))?;
+
+    let args = [
+        json!(question.as_bytes()),
+        json!(options_count),
+        json!(oracle.to_string()),
+        json!(PLATFORM_FEE_BPS),
+        json!(platform_admin.to_string()),
+        json!(end_time_ms.to_string()),
+    ]
+    .into_iter()
+    .map(SuiJsonValue::new)
+    .collect::<Result<Vec<_>, _>>()
+    .map_err(|e| TxError::Build(format!("Invalid create_market argument: {}", e)))?;
+
+    Ok((MoveCall::new("create_market", args), oracle))
+}
+
+async fn dry_run_create(market: &NewMarket) -> Result<DryRunReport, TxError> {
+    let executor = Executor::from_env().await?;
+    let (call, _) = create_market_call(
+        executor.sender(),
+        &market.name,
+        market.options_count,
+        market.end_time_ms,
+        market.oracle,
+    )?;
+    executor.dry_run(call).await
 }
@@ -1,25 +1,58 @@
+use crate::auth::{self, AuthSession};
 use crate::entities::favorite;
 use axum::{
-    extract::{Path, State},
+    extract::{Extension, Path, State},
     http::StatusCode,
     Json,
 };
 use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
 use serde::Deserialize;
+use utoipa::ToSchema;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct AddFavorite {
     pub wallet_address: String,
     pub contract_id: i32,
 }
 
+/// Only the signed-in wallet may edit its own favorites
+fn authorize_wallet(
+    session: &AuthSession,
+    wallet_address: &str,
+) -> Result<String, (StatusCode, String)> {
+    let wallet = auth::parse_address(wallet_address)?;
+    if wallet != session.address {
+        return Err((
+            StatusCode::FORBIDDEN,
+            "Session does not belong to this wallet".to_string(),
+        ));
+    }
+    Ok(wallet.to_string())
+}
+
+#[utoipa::path(
+    post,
+    path = "/favorites",
+    security(("bearer" = [])),
+    tag = "favorites",
+    request_body = AddFavorite,
+    responses(
+        (status = 200, description = "Favorite entry (existing or new)", body = favorite::Model),
+        (status = 401, description = "Missing or invalid session", body = String),
+        (status = 403, description = "Session belongs to another wallet", body = String),
+        (status = 500, description = "Database error", body = String),
+    )
+)]
 pub async fn add_favorite(
     State(db): State<DatabaseConnection>,
+    Extension(session): Extension<AuthSession>,
     Json(payload): Json<AddFavorite>,
 ) -> Result<Json<favorite::Model>, (StatusCode, String)> {
+    let wallet_address = authorize_wallet(&session, &payload.wallet_address)?;
+
     // Check if already exists
     let exists = favorite::Entity::find()
-        .filter(favorite::Column::WalletAddress.eq(&payload.wallet_address))
+        .filter(favorite::Column::WalletAddress.eq(&wallet_address))
         .filter(favorite::Column::ContractId.eq(payload.contract_id))
         .one(&db)
         .await
@@ -30,7 +63,7 @@ pub async fn add_favorite(
     }
 
     let new_favorite = favorite::ActiveModel {
-        wallet_address: Set(payload.wallet_address),
+        wallet_address: Set(wallet_address),
         contract_id: Set(payload.contract_id),
         ..Default::default()
     };
@@ -43,12 +76,28 @@ pub async fn add_favorite(
     Ok(Json(saved))
 }
 
+#[utoipa::path(
+    delete,
+    path = "/favorites",
+    security(("bearer" = [])),
+    tag = "favorites",
+    request_body = AddFavorite,
+    responses(
+        (status = 204, description = "Removed (or was not present)"),
+        (status = 401, description = "Missing or invalid session", body = String),
+        (status = 403, description = "Session belongs to another wallet", body = String),
+        (status = 500, description = "Database error", body = String),
+    )
+)]
 pub async fn remove_favorite(
     State(db): State<DatabaseConnection>,
+    Extension(session): Extension<AuthSession>,
     Json(payload): Json<AddFavorite>,
 ) -> Result<StatusCode, (StatusCode, String)> {
+    let wallet_address = authorize_wallet(&session, &payload.wallet_address)?;
+
     let result = favorite::Entity::delete_many()
-        .filter(favorite::Column::WalletAddress.eq(&payload.wallet_address))
+        .filter(favorite::Column::WalletAddress.eq(&wallet_address))
         .filter(favorite::Column::ContractId.eq(payload.contract_id))
         .exec(&db)
         .await
@@ -62,12 +111,25 @@ pub async fn remove_favorite(
     Ok(StatusCode::NO_CONTENT)
 }
 
+#[utoipa::path(
+    get,
+    path = "/favorites/{wallet}",
+    tag = "favorites",
+    params(("wallet" = String, Path, description = "Wallet address")),
+    responses(
+        (status = 200, description = "Favorited contract ids", body = [i32]),
+        (status = 400, description = "Invalid wallet address", body = String),
+        (status = 500, description = "Database error", body = String),
+    )
+)]
 pub async fn get_favorites(
     State(db): State<DatabaseConnection>,
     Path(wallet_address): Path<String>,
 ) -> Result<Json<Vec<i32>>, (StatusCode, String)> {
+    // Stored in canonical form, whatever spelling the caller used
+    let wallet = auth::parse_address(&wallet_address)?;
     let favorites = favorite::Entity::find()
-        .filter(favorite::Column::WalletAddress.eq(wallet_address))
+        .filter(favorite::Column::WalletAddress.eq(wallet.to_string()))
         .all(&db)
         .await
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
@@ -0,0 +1,149 @@
+use crate::auth::AuthSession;
+use crate::entities::{fee_withdrawal, tx_job};
+use crate::fees::{self, FeeConfig, Withdrawable};
+use crate::jobs::JobRun;
+use crate::tx::GasCost;
+use axum::{
+    extract::{Extension, Json, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use sea_orm::{DatabaseConnection, EntityTrait, QueryOrder};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use sui_sdk::types::base_types::ObjectID;
+use utoipa::ToSchema;
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct WithdrawFeesRequest {
+    /// Limit to these markets; all registered markets by default
+    pub market_ids: Option<Vec<String>>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct WithdrawFeesResponse {
+    /// Markets withdrawn, empty when none held enough fees
+    pub markets: Vec<Withdrawable>,
+    /// Sum of `markets` amounts (MIST)
+    pub total: u64,
+    pub job_id: Option<i32>,
+    pub digest: Option<String>,
+    pub gas_used: Option<GasCost>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct MarketRevenue {
+    pub market_address: String,
+    pub contract_id: Option<i32>,
+    /// Total withdrawn (MIST)
+    pub total: i64,
+    pub withdrawals: u64,
+    pub last_withdrawn_at: String,
+}
+
+/// Withdraw one batch of platform fees from markets administered by the backend signer
+#[utoipa::path(
+    post,
+    path = "/admin/fees/withdraw",
+    security(("bearer" = [])),
+    tag = "admin",
+    request_body = WithdrawFeesRequest,
+    responses(
+        (status = 200, description = "Fees withdrawn, or nothing to withdraw", body = WithdrawFeesResponse),
+        (status = 202, description = "Withdrawal sent but not yet confirmed; poll the job", body = tx_job::Model),
+        (status = 400, description = "Invalid market id", body = String),
+        (status = 409, description = "Another fee withdrawal is in flight", body = String),
+        (status = 422, description = "Transaction could not be built or aborted on-chain", body = String),
+        (status = 500, description = "Backend misconfigured", body = String),
+        (status = 502, description = "Fullnode unreachable", body = String),
+        (status = 401, description = "Missing or invalid session", body = String),
+        (status = 403, description = "Caller lacks the required role", body = String),
+    )
+)]
+pub async fn withdraw_fees(
+    State(db): State<DatabaseConnection>,
+    Extension(session): Extension<AuthSession>,
+    Json(payload): Json<WithdrawFeesRequest>,
+) -> Result<Response, (StatusCode, String)> {
+    let only = payload
+        .market_ids
+        .map(|ids| {
+            ids.iter()
+                .map(|id| ObjectID::from_str(id.trim()))
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .transpose()
+        .map_err(|e| (StatusCode::BAD_REQUEST, format!("Invalid Market ID: {}", e)))?;
+
+    let withdrawn = fees::withdraw(
+        &db,
+        session.address,
+        only.as_deref(),
+        &FeeConfig::from_env(),
+    )
+    .await?;
+
+    let response = match withdrawn {
+        None => Json(WithdrawFeesResponse {
+            markets: vec![],
+            total: 0,
+            job_id: None,
+            digest: None,
+            gas_used: None,
+        })
+        .into_response(),
+        Some((_, JobRun::Pending(job))) => (StatusCode::ACCEPTED, Json(job)).into_response(),
+        Some((markets, JobRun::Confirmed { job, outcome })) => Json(WithdrawFeesResponse {
+            total: markets.iter().map(|m| m.amount).sum(),
+            markets,
+            job_id: Some(job.id),
+            digest: Some(outcome.digest),
+            gas_used: Some(outcome.gas),
+        })
+        .into_response(),
+    };
+    Ok(response)
+}
+
+/// Platform fees withdrawn so far, per market
+#[utoipa::path(
+    get,
+    path = "/admin/fees/revenue",
+    security(("bearer" = [])),
+    tag = "admin",
+    responses(
+        (status = 200, description = "Revenue per market, largest first", body = [MarketRevenue]),
+        (status = 401, description = "Missing or invalid session", body = String),
+        (status = 403, description = "Caller lacks the required role", body = String),
+    )
+)]
+pub async fn list_revenue(
+    State(db): State<DatabaseConnection>,
+) -> Result<Json<Vec<MarketRevenue>>, (StatusCode, String)> {
+    let rows = fee_withdrawal::Entity::find()
+        .order_by_asc(fee_withdrawal::Column::Id)
+        .all(&db)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let mut by_market: BTreeMap<String, MarketRevenue> = BTreeMap::new();
+    for row in rows {
+        let entry = by_market
+            .entry(row.market_address.clone())
+            .or_insert_with(|| MarketRevenue {
+                market_address: row.market_address.clone(),
+                contract_id: row.contract_id,
+                total: 0,
+                withdrawals: 0,
+                last_withdrawn_at: String::new(),
+            });
+        entry.total += row.amount;
+        entry.withdrawals += 1;
+        entry.last_withdrawn_at = row.created_at;
+    }
+
+    let mut revenue: Vec<MarketRevenue> = by_market.into_values().collect();
+    revenue.sort_by_key(|r| std::cmp::Reverse(r.total));
+    Ok(Json(revenue))
+}
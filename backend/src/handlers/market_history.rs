@@ -7,12 +7,25 @@ use axum::{
 use chrono::Utc;
 use sea_orm::{DatabaseConnection, EntityTrait};
 use serde::Deserialize;
+use utoipa::IntoParams;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct HistoryParams {
+    /// One of 5m, 1h, 6h, 1d, 1w, 1M (default)
     pub range: Option<String>,
 }
 
+#[utoipa::path(
+    get,
+    path = "/contracts/{id}/history",
+    tag = "contracts",
+    params(("id" = i32, Path, description = "Contract id"), HistoryParams),
+    responses(
+        (status = 200, description = "Price history points", body = [market_history::Model]),
+        (status = 404, description = "Contract not found", body = String),
+    )
+)]
 pub async fn get_contract_history(
     State(db): State<DatabaseConnection>,
     Path(contract_id): Path<i32>,
@@ -1,7 +1,16 @@
+pub mod account;
+pub mod audit;
+pub mod auth;
 pub mod cancel;
 pub mod category;
 pub mod config;
 pub mod contract;
 pub mod favorite;
+pub mod fees;
 pub mod market_history;
 pub mod oracle;
+pub mod resolution;
+pub mod resolver;
+pub mod sponsor;
+pub mod stream;
+pub mod tx_job;
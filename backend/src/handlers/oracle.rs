@@ -1,158 +1,290 @@
+use crate::auth::AuthSession;
+use crate::entities::{contract, market_resolution, tx_job};
+use crate::jobs::{self, JobRun, JobSpec};
+use crate::preflight;
+use crate::tx::{DryRunParams, Executor, GasCost, MoveCall, TxError, TxErrorBody};
+use crate::validation::ValidationErrors;
 use axum::{
-    extract::{Json, State},
+    extract::{Extension, Json, Query, State},
     http::StatusCode,
-    response::IntoResponse,
+    response::{IntoResponse, Response},
 };
-use sea_orm::DatabaseConnection;
+use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-use shared_crypto::intent::Intent;
-use std::path::PathBuf;
 use std::str::FromStr;
-use sui_keys::keystore::{AccountKeystore, FileBasedKeystore};
-use sui_sdk::{
-    json::SuiJsonValue,
-    rpc_types::{SuiTransactionBlockEffectsAPI, SuiTransactionBlockResponseOptions},
-    types::{base_types::ObjectID, transaction::Transaction},
-    SuiClientBuilder,
-};
+use sui_sdk::{json::SuiJsonValue, types::base_types::ObjectID};
+use utoipa::ToSchema;
+
+const MAX_SOURCE_URL_LEN: usize = 2048;
+const MAX_NOTES_LEN: usize = 2000;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, ToSchema)]
 pub struct ResolveMarketRequest {
     pub market_id: String,
-    pub winner: u8, // Winner option index (0, 1, 2, etc.) for multi-option markets
+    /// Winner option index (0, 1, 2, etc.); give this or `outcome`
+    pub winner: Option<u8>,
+    /// Winner option label from the market's `options`, matched case-insensitively
+    pub outcome: Option<String>,
+    /// Where the result was taken from, e.g. an official results page
+    pub source_url: Option<String>,
+    pub notes: Option<String>,
 }
 
-#[derive(Serialize)]
+/// Operator context stored with a resolution
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ResolutionSource {
+    pub source_url: Option<String>,
+    pub notes: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
 pub struct ResolveMarketResponse {
     pub digest: String,
     pub status: String,
+    pub gas_budget: u64,
+    pub gas_used: GasCost,
+    /// Transaction job that carried the resolution
+    pub job_id: i32,
 }
 
+#[utoipa::path(
+    post,
+    path = "/oracle/resolve",
+    security(("bearer" = [])),
+    tag = "oracle",
+    request_body = ResolveMarketRequest,
+    params(DryRunParams),
+    responses(
+        (status = 200, description = "Resolution submitted; a DryRunReport with `dry_run=true`", body = ResolveMarketResponse),
+        (status = 202, description = "Resolution sent but not yet confirmed; poll the job", body = tx_job::Model),
+        (status = 400, description = "Unknown outcome label or invalid fields (ValidationErrors), or outcome out of range (`invalid_outcome`)", body = TxErrorBody),
+        (status = 403, description = "Backend signer is not the oracle (`not_authorized`), or caller lacks the required role", body = TxErrorBody),
+        (status = 409, description = "Market already resolved or cancelled, or another transaction is in flight", body = TxErrorBody),
+        (status = 422, description = "Transaction could not be built or failed on-chain", body = TxErrorBody),
+        (status = 500, description = "Backend misconfigured or database error", body = TxErrorBody),
+        (status = 502, description = "Fullnode unreachable", body = TxErrorBody),
+        (status = 401, description = "Missing or invalid session", body = String),
+    )
+)]
 pub async fn resolve_market(
-    State(_db): State<DatabaseConnection>,
+    State(db): State<DatabaseConnection>,
+    Extension(session): Extension<AuthSession>,
+    Query(params): Query<DryRunParams>,
     Json(payload): Json<ResolveMarketRequest>,
-) -> Result<impl IntoResponse, (StatusCode, String)> {
-    // 1. Load config
-    let network = std::env::var("SUI_NETWORK")
-        .unwrap_or_else(|_| "https://fullnode.testnet.sui.io:443".to_string());
-    let package_id_str = std::env::var("PACKAGE_ID").map_err(|_| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            "PACKAGE_ID not set".to_string(),
-        )
-    })?;
-
-    // 2. Setup Client
-    let client = SuiClientBuilder::default()
-        .build(&network)
+) -> Result<Response, Response> {
+    let market = contract::Entity::find()
+        .filter(contract::Column::Address.eq(&payload.market_id))
+        .one(&db)
         .await
-        .map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Failed to create Sui client: {}", e),
-            )
-        })?;
-
-    // 3. Load Keystore (Admin Wallet) - same approach as contract.rs
-    let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
-    let keystore_path = PathBuf::from(home).join(".sui/sui_config/sui.keystore");
-
-    let keystore = FileBasedKeystore::load_or_create(&keystore_path).map_err(|e| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Failed to load keystore: {}", e),
-        )
-    })?;
-
-    let addresses = keystore.addresses();
-    if addresses.is_empty() {
-        return Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            "No accounts found in sui.keystore".to_string(),
-        ));
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response())?;
+    let (winner, source) =
+        validate_resolution(market.as_ref(), &payload).map_err(IntoResponse::into_response)?;
+
+    // Same pre-flight checks as a real submit, so both report the same error
+    if params.enabled() {
+        let executor = Executor::from_env()
+            .await
+            .map_err(IntoResponse::into_response)?;
+        preflight::check_resolve(&db, &executor, &payload.market_id, winner)
+            .await
+            .map_err(IntoResponse::into_response)?;
+        let call = resolve_call(&payload.market_id, winner).map_err(IntoResponse::into_response)?;
+        let report = executor
+            .dry_run(call)
+            .await
+            .map_err(IntoResponse::into_response)?;
+        return Ok(Json(report).into_response());
     }
-    let sender = addresses[0];
-
-    println!("Oracle: Using Admin Account: {}", sender);
-
-    // 4. Prepare Arguments
-    let market_id = ObjectID::from_str(&payload.market_id)
-        .map_err(|e| (StatusCode::BAD_REQUEST, format!("Invalid Market ID: {}", e)))?;
-    let package_id = ObjectID::from_str(&package_id_str).map_err(|e| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Invalid Package ID: {}", e),
-        )
-    })?;
-
-    // 5. Build Transaction
-    let gas_price = client
-        .read_api()
-        .get_reference_gas_price()
-        .await
-        .unwrap_or(1000);
-
-    let market_arg = SuiJsonValue::from_object_id(market_id);
-    let winner_arg = SuiJsonValue::new(json!(payload.winner)).map_err(|e| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Failed to create winner arg: {}", e),
-        )
-    })?;
-
-    let tx_data = client
-        .transaction_builder()
-        .move_call(
-            sender,
-            package_id,
-            "market",
-            "resolve_market",
-            vec![], // type_args
-            vec![market_arg, winner_arg],
-            None,       // gas
-            50_000_000, // gas_budget
-            Some(gas_price),
-        )
-        .await
-        .map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Failed to build transaction data: {}", e),
-            )
-        })?;
-
-    // Sign using keystore
-    let signature = keystore
-        .sign_secure(&sender, &tx_data, Intent::sui_transaction())
+
+    let run = execute_resolve_market(&db, session.address, &payload.market_id, winner, source)
         .await
-        .map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Failed to sign transaction: {}", e),
-            )
-        })?;
-
-    // Execute
-    let response = client
-        .quorum_driver_api()
-        .execute_transaction_block(
-            Transaction::from_data(tx_data, vec![signature]),
-            SuiTransactionBlockResponseOptions::new()
-                .with_effects()
-                .with_events(),
-            None,
-        )
+        .map_err(IntoResponse::into_response)?;
+    match run {
+        JobRun::Confirmed { job, outcome } => Ok(Json(ResolveMarketResponse {
+            digest: outcome.digest,
+            status: "Success".to_string(),
+            gas_budget: outcome.gas_budget,
+            gas_used: outcome.gas,
+            job_id: job.id,
+        })
+        .into_response()),
+        JobRun::Pending(job) => Ok((StatusCode::ACCEPTED, Json(job)).into_response()),
+    }
+}
+
+/// Check the market on-chain, then resolve it through a transaction job
+pub async fn execute_resolve_market(
+    db: &DatabaseConnection,
+    actor: impl ToString,
+    market_id: &str,
+    winner: u8,
+    source: ResolutionSource,
+) -> Result<JobRun, TxError> {
+    let spec = JobSpec::ResolveMarket {
+        market_id: market_id.to_string(),
+        winner,
+        source,
+    };
+    let checked = match Executor::from_env().await {
+        Ok(executor) => preflight::check_resolve(db, &executor, market_id, winner).await,
+        Err(e) => Err(e),
+    };
+    if let Err(e) = checked {
+        return Err(jobs::reject(db, actor, &spec, e).await);
+    }
+    jobs::run(db, actor, spec).await
+}
+
+/// Option labels of a registered market; markets without options are binary Yes/No
+pub fn option_labels(market: &contract::Model) -> Vec<String> {
+    market
+        .options
+        .as_deref()
+        .and_then(|o| serde_json::from_str(o).ok())
+        .unwrap_or_else(|| vec!["Yes".to_string(), "No".to_string()])
+}
+
+/// Winner index from `winner` or the `outcome` label of `market` (None when
+/// not registered), and the trimmed source fields
+fn validate_resolution(
+    market: Option<&contract::Model>,
+    payload: &ResolveMarketRequest,
+) -> Result<(u8, ResolutionSource), ValidationErrors> {
+    let mut errors = ValidationErrors::new();
+
+    let by_label = match payload.outcome.as_deref().map(str::trim) {
+        None | Some("") => None,
+        Some(label) => match market {
+            None => {
+                errors.add(
+                    "outcome",
+                    "market is not registered; resolve it by `winner` index",
+                );
+                None
+            }
+            Some(market) => {
+                let labels = option_labels(market);
+                let index = labels
+                    .iter()
+                    .position(|l| l.trim().eq_ignore_ascii_case(label));
+                if index.is_none() {
+                    errors.add(
+                        "outcome",
+                        format!("unknown outcome {:?}; options are {:?}", label, labels),
+                    );
+                }
+                index.map(|i| i as u8)
+            }
+        },
+    };
+
+    let winner = match (payload.winner, by_label) {
+        (Some(winner), Some(index)) if winner != index => {
+            errors.add(
+                "winner",
+                format!(
+                    "index {} does not match outcome label (index {})",
+                    winner, index
+                ),
+            );
+            None
+        }
+        (Some(winner), _) => Some(winner),
+        (None, Some(index)) => Some(index),
+        (None, None) => {
+            // An unknown label has already been reported
+            if errors.is_empty() {
+                errors.add("winner", "give the winner index or the outcome label");
+            }
+            None
+        }
+    };
+
+    let source = validate_source(
+        payload.source_url.as_deref(),
+        payload.notes.as_deref(),
+        &mut errors,
+    );
+    match winner {
+        Some(winner) if errors.is_empty() => Ok((winner, source)),
+        _ => Err(errors),
+    }
+}
+
+/// Trimmed `source_url` and `notes`, adding any problems to `errors`
+pub fn validate_source(
+    source_url: Option<&str>,
+    notes: Option<&str>,
+    errors: &mut ValidationErrors,
+) -> ResolutionSource {
+    let source_url = source_url.map(str::trim).filter(|u| !u.is_empty());
+    if let Some(url) = source_url {
+        if !(url.starts_with("https://") || url.starts_with("http://"))
+            || url.contains(char::is_whitespace)
+        {
+            errors.add("source_url", "must be an http(s) URL");
+        } else if url.len() > MAX_SOURCE_URL_LEN {
+            errors.add(
+                "source_url",
+                format!("must be at most {} characters", MAX_SOURCE_URL_LEN),
+            );
+        }
+    }
+    let notes = notes.map(str::trim).filter(|n| !n.is_empty());
+    if notes.is_some_and(|n| n.chars().count() > MAX_NOTES_LEN) {
+        errors.add(
+            "notes",
+            format!("must be at most {} characters", MAX_NOTES_LEN),
+        );
+    }
+
+    ResolutionSource {
+        source_url: source_url.map(str::to_string),
+        notes: notes.map(str::to_string),
+    }
+}
+
+/// Store the record of a confirmed resolution
+pub async fn record_resolution(
+    db: &DatabaseConnection,
+    market_id: &str,
+    winner: u8,
+    source: &ResolutionSource,
+    digest: &str,
+    resolved_by: &str,
+) -> Result<market_resolution::Model, String> {
+    let market = contract::Entity::find()
+        .filter(contract::Column::Address.eq(market_id))
+        .one(db)
         .await
-        .map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Failed to execute transaction: {}", e),
-            )
-        })?;
-
-    Ok(Json(ResolveMarketResponse {
-        digest: response.digest.to_string(),
-        status: format!("{:?}", response.effects.as_ref().map(|e| e.status())),
-    }))
+        .map_err(|e| e.to_string())?;
+
+    market_resolution::ActiveModel {
+        contract_id: Set(market.as_ref().map(|m| m.id)),
+        market_address: Set(market_id.to_string()),
+        winner: Set(winner as i32),
+        outcome_label: Set(market.and_then(|m| option_labels(&m).get(winner as usize).cloned())),
+        source_url: Set(source.source_url.clone()),
+        notes: Set(source.notes.clone()),
+        digest: Set(digest.to_string()),
+        resolved_by: Set(resolved_by.to_string()),
+        created_at: Set(chrono::Utc::now().to_rfc3339()),
+        ..Default::default()
+    }
+    .insert(db)
+    .await
+    .map_err(|e| format!("Market resolved but resolution not recorded: {}", e))
+}
+
+pub fn resolve_call(market_id: &str, winner: u8) -> Result<MoveCall, TxError> {
+    let market_id = ObjectID::from_str(market_id)
+        .map_err(|e| TxError::Build(format!("Invalid Market ID: {}", e)))?;
+    let winner_arg = SuiJsonValue::new(json!(winner))
+        .map_err(|e| TxError::Build(format!("Failed to create winner arg: {}", e)))?;
+
+    Ok(MoveCall::new(
+        "resolve_market",
+        vec![SuiJsonValue::from_object_id(market_id), winner_arg],
+    ))
 }
@@ -0,0 +1,455 @@
+//! Offline resolution: the backend prepares an unsigned `resolve_market`
+//! transaction for the market's oracle (typically a multisig address), the
+//! signers sign the bytes out of band (e.g. `sui keytool sign` and
+//! `sui keytool multi-sig-combine-partial-sig`), and the backend assembles and
+//! submits the result. The hot backend key never touches these markets.
+
+use crate::audit::{self, AuditEntry};
+use crate::auth::AuthSession;
+use crate::entities::contract;
+use crate::entities::pending_resolution::{self, ResolutionStatus};
+use crate::entities::tx_job;
+use crate::handlers::oracle::{self, ResolutionSource};
+use crate::jobs::{self, JobRun, JobSpec};
+use crate::tx::{self, GasBudgetConfig, SignedTx};
+use crate::validation::ValidationErrors;
+use axum::{
+    extract::{Extension, Json, Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, Set,
+};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use sui_sdk::types::{
+    base_types::SuiAddress,
+    crypto::ToFromBytes,
+    signature::GenericSignature,
+    transaction::{Transaction, TransactionData},
+};
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct PrepareResolutionRequest {
+    pub market_id: String,
+    pub winner: u8,
+    /// Oracle address that will sign and pay gas; defaults to the market's recorded oracle
+    pub sender: Option<String>,
+    /// Where the result was taken from; stored with the resolution once executed
+    pub source_url: Option<String>,
+    pub notes: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct SubmitResolutionRequest {
+    /// Base64 serialized signatures over `tx_bytes`: one per sender, a multisig
+    /// sender supplies the combined multisig signature
+    pub signatures: Vec<String>,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListResolutionParams {
+    pub status: Option<ResolutionStatus>,
+    pub market_id: Option<String>,
+}
+
+fn now() -> String {
+    chrono::Utc::now().to_rfc3339()
+}
+
+/// The resolution awaiting signatures for a market, if any
+pub async fn awaiting_for(
+    db: &DatabaseConnection,
+    market_address: &str,
+) -> Option<pending_resolution::Model> {
+    pending_resolution::Entity::find()
+        .filter(pending_resolution::Column::MarketAddress.eq(market_address))
+        .filter(pending_resolution::Column::Status.eq(ResolutionStatus::AwaitingSignature))
+        .one(db)
+        .await
+        .ok()
+        .flatten()
+}
+
+async fn find_pending(
+    db: &DatabaseConnection,
+    id: i32,
+) -> Result<pending_resolution::Model, (StatusCode, String)> {
+    pending_resolution::Entity::find_by_id(id)
+        .one(db)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, "Resolution not found".to_string()))
+}
+
+fn require_awaiting(model: &pending_resolution::Model) -> Result<(), (StatusCode, String)> {
+    if model.status != ResolutionStatus::AwaitingSignature {
+        return Err((
+            StatusCode::CONFLICT,
+            format!("Resolution {} is {:?}", model.id, model.status),
+        ));
+    }
+    Ok(())
+}
+
+/// Move a resolution from `from` to `to`; `false` when another request moved it first
+async fn transition(
+    db: &DatabaseConnection,
+    id: i32,
+    from: ResolutionStatus,
+    to: ResolutionStatus,
+    last_error: Option<String>,
+) -> Result<bool, sea_orm::DbErr> {
+    let updated = pending_resolution::Entity::update_many()
+        .set(pending_resolution::ActiveModel {
+            status: Set(to),
+            last_error: Set(last_error),
+            updated_at: Set(now()),
+            ..Default::default()
+        })
+        .filter(pending_resolution::Column::Id.eq(id))
+        .filter(pending_resolution::Column::Status.eq(from))
+        .exec(db)
+        .await?;
+    Ok(updated.rows_affected == 1)
+}
+
+/// Settle a submitted resolution once its job confirms or fails
+pub async fn finish(
+    db: &DatabaseConnection,
+    id: i32,
+    status: ResolutionStatus,
+    last_error: Option<String>,
+) {
+    if let Err(e) = transition(db, id, ResolutionStatus::Submitting, status, last_error).await {
+        eprintln!("Oracle: Failed to update resolution {}: {}", id, e);
+    }
+}
+
+/// Build an unsigned resolution for an external oracle to sign
+#[utoipa::path(
+    post,
+    path = "/oracle/resolutions",
+    security(("bearer" = [])),
+    tag = "oracle",
+    request_body = PrepareResolutionRequest,
+    responses(
+        (status = 200, description = "Unsigned transaction; sign `tx_bytes` and submit the signatures", body = pending_resolution::Model),
+        (status = 400, description = "Invalid sender, no oracle on record, or invalid `source_url`/`notes` (ValidationErrors)", body = String),
+        (status = 409, description = "Another resolution or transaction for this market is open", body = String),
+        (status = 422, description = "Transaction could not be built or would abort", body = String),
+        (status = 502, description = "Fullnode unreachable", body = String),
+        (status = 401, description = "Missing or invalid session", body = String),
+        (status = 403, description = "Caller lacks the required role", body = String),
+    )
+)]
+pub async fn prepare_resolution(
+    State(db): State<DatabaseConnection>,
+    Extension(session): Extension<AuthSession>,
+    Json(payload): Json<PrepareResolutionRequest>,
+) -> Result<Json<pending_resolution::Model>, Response> {
+    let mut errors = ValidationErrors::new();
+    let source = oracle::validate_source(
+        payload.source_url.as_deref(),
+        payload.notes.as_deref(),
+        &mut errors,
+    );
+    errors
+        .into_result(())
+        .map_err(IntoResponse::into_response)?;
+
+    prepare(&db, session, &payload, source)
+        .await
+        .map(Json)
+        .map_err(IntoResponse::into_response)
+}
+
+async fn prepare(
+    db: &DatabaseConnection,
+    session: AuthSession,
+    payload: &PrepareResolutionRequest,
+    source: ResolutionSource,
+) -> Result<pending_resolution::Model, (StatusCode, String)> {
+    if let Some(existing) = awaiting_for(db, &payload.market_id).await {
+        return Err((
+            StatusCode::CONFLICT,
+            format!(
+                "Resolution {} for this market is awaiting signatures; abandon it first",
+                existing.id
+            ),
+        ));
+    }
+    if let Some(job) = jobs::open_job_for(db, &payload.market_id).await {
+        return Err((
+            StatusCode::CONFLICT,
+            format!(
+                "Job {} ({}) for this market has not finished",
+                job.id, job.kind
+            ),
+        ));
+    }
+
+    let market = contract::Entity::find()
+        .filter(contract::Column::Address.eq(&payload.market_id))
+        .one(db)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let sender = payload
+        .sender
+        .clone()
+        .or_else(|| market.as_ref().and_then(|m| m.oracle_address.clone()))
+        .ok_or((
+            StatusCode::BAD_REQUEST,
+            "No oracle on record for this market; pass `sender`".to_string(),
+        ))?;
+    let sender = SuiAddress::from_str(sender.trim())
+        .map_err(|e| (StatusCode::BAD_REQUEST, format!("Invalid sender: {}", e)))?;
+
+    let call = oracle::resolve_call(&payload.market_id, payload.winner)?;
+    let client = tx::connect().await?;
+    let (tx_data, gas_budget) =
+        tx::build_unsigned(&client, &GasBudgetConfig::from_env(), sender, call).await?;
+    let tx_bytes = bcs::to_bytes(&tx_data).map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to serialize transaction: {}", e),
+        )
+    })?;
+
+    let inserted = pending_resolution::ActiveModel {
+        contract_id: Set(market.map(|m| m.id)),
+        market_address: Set(payload.market_id.clone()),
+        winner: Set(payload.winner as i32),
+        sender: Set(sender.to_string()),
+        tx_bytes: Set(BASE64.encode(tx_bytes)),
+        digest: Set(tx_data.digest().to_string()),
+        gas_budget: Set(gas_budget as i64),
+        source_url: Set(source.source_url),
+        notes: Set(source.notes),
+        status: Set(ResolutionStatus::AwaitingSignature),
+        requested_by: Set(session.address.to_string()),
+        created_at: Set(now()),
+        updated_at: Set(now()),
+        ..Default::default()
+    }
+    .insert(db)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    audit::record(
+        db,
+        AuditEntry::new(session.address, "prepare_resolution")
+            .contract(inserted.contract_id)
+            .market(&inserted.market_address)
+            .payload(payload)
+            .digest(Some(inserted.digest.clone())),
+    )
+    .await;
+
+    Ok(inserted)
+}
+
+/// Resolutions prepared for offline signing, newest first
+#[utoipa::path(
+    get,
+    path = "/oracle/resolutions",
+    security(("bearer" = [])),
+    tag = "oracle",
+    params(ListResolutionParams),
+    responses(
+        (status = 200, description = "Prepared resolutions", body = [pending_resolution::Model]),
+        (status = 401, description = "Missing or invalid session", body = String),
+        (status = 403, description = "Caller lacks the required role", body = String),
+    )
+)]
+pub async fn list_resolutions(
+    State(db): State<DatabaseConnection>,
+    Query(params): Query<ListResolutionParams>,
+) -> Result<Json<Vec<pending_resolution::Model>>, (StatusCode, String)> {
+    let mut query =
+        pending_resolution::Entity::find().order_by_desc(pending_resolution::Column::Id);
+    if let Some(status) = params.status {
+        query = query.filter(pending_resolution::Column::Status.eq(status));
+    }
+    if let Some(market_id) = params.market_id {
+        query = query.filter(pending_resolution::Column::MarketAddress.eq(market_id));
+    }
+
+    query
+        .all(&db)
+        .await
+        .map(Json)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+/// Assemble the prepared transaction with externally produced signatures and
+/// execute it as a transaction job, so a lost response is reconciled by digest
+#[utoipa::path(
+    post,
+    path = "/oracle/resolutions/{id}/submit",
+    security(("bearer" = [])),
+    tag = "oracle",
+    params(("id" = i32, Path, description = "Resolution id")),
+    request_body = SubmitResolutionRequest,
+    responses(
+        (status = 200, description = "Resolution executed", body = pending_resolution::Model),
+        (status = 202, description = "Sent but not yet confirmed; poll the job", body = tx_job::Model),
+        (status = 400, description = "Malformed signature", body = String),
+        (status = 404, description = "Resolution not found", body = String),
+        (status = 409, description = "Resolution is no longer awaiting signatures, or another transaction for the market is in flight", body = String),
+        (status = 422, description = "Aborted on-chain", body = String),
+        (status = 502, description = "Rejected by or could not reach the fullnode; may be retried", body = String),
+        (status = 401, description = "Missing or invalid session", body = String),
+        (status = 403, description = "Caller lacks the required role", body = String),
+    )
+)]
+pub async fn submit_resolution(
+    State(db): State<DatabaseConnection>,
+    Extension(session): Extension<AuthSession>,
+    Path(id): Path<i32>,
+    Json(payload): Json<SubmitResolutionRequest>,
+) -> Result<Response, (StatusCode, String)> {
+    let pending = find_pending(&db, id).await?;
+    require_awaiting(&pending)?;
+
+    if payload.signatures.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "At least one signature is required".to_string(),
+        ));
+    }
+    let signatures = payload
+        .signatures
+        .iter()
+        .map(|s| {
+            let bytes = BASE64
+                .decode(s.trim())
+                .map_err(|e| format!("Signature is not valid base64: {}", e))?;
+            GenericSignature::from_bytes(&bytes)
+                .map_err(|e| format!("Unsupported signature: {}", e))
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+
+    let tx_data: TransactionData = BASE64
+        .decode(&pending.tx_bytes)
+        .ok()
+        .and_then(|b| bcs::from_bytes(&b).ok())
+        .ok_or((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Stored transaction could not be decoded".to_string(),
+        ))?;
+    let transaction = Transaction::from_generic_sig_data(tx_data, signatures);
+    let signed = SignedTx {
+        digest: transaction.digest().to_string(),
+        transaction,
+        gas_budget: pending.gas_budget as u64,
+        label: "resolve_market".to_string(),
+    };
+
+    // Only one of two concurrent submissions gets to send
+    let claimed = transition(
+        &db,
+        id,
+        ResolutionStatus::AwaitingSignature,
+        ResolutionStatus::Submitting,
+        None,
+    )
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if !claimed {
+        return Err((
+            StatusCode::CONFLICT,
+            format!("Resolution {} is no longer awaiting signatures", id),
+        ));
+    }
+
+    // The job records the audit entry and settles the resolution
+    let spec = JobSpec::SubmitResolution {
+        resolution_id: id,
+        market_id: pending.market_address.clone(),
+        winner: pending.winner as u8,
+        sender: pending.sender.clone(),
+        source: ResolutionSource {
+            source_url: pending.source_url.clone(),
+            notes: pending.notes.clone(),
+        },
+    };
+    match jobs::run_signed(&db, session.address, spec, signed).await {
+        Ok(JobRun::Confirmed { outcome, .. }) => {
+            println!(
+                "Oracle: offline resolution {} executed ({})",
+                id, outcome.digest
+            );
+            Ok(Json(find_pending(&db, id).await?).into_response())
+        }
+        Ok(JobRun::Pending(job)) => Ok((StatusCode::ACCEPTED, Json(job)).into_response()),
+        Err(e) => {
+            // Refused before a job was recorded, the signatures can be sent again;
+            // a job that failed has settled the resolution already
+            finish(
+                &db,
+                id,
+                ResolutionStatus::AwaitingSignature,
+                Some(e.to_string()),
+            )
+            .await;
+            Err(e.into())
+        }
+    }
+}
+
+/// Discard a prepared resolution, e.g. when its gas coin was spent elsewhere
+#[utoipa::path(
+    delete,
+    path = "/oracle/resolutions/{id}",
+    security(("bearer" = [])),
+    tag = "oracle",
+    params(("id" = i32, Path, description = "Resolution id")),
+    responses(
+        (status = 200, description = "Abandoned resolution", body = pending_resolution::Model),
+        (status = 404, description = "Resolution not found", body = String),
+        (status = 409, description = "Resolution is no longer awaiting signatures", body = String),
+        (status = 401, description = "Missing or invalid session", body = String),
+        (status = 403, description = "Caller lacks the required role", body = String),
+    )
+)]
+pub async fn abandon_resolution(
+    State(db): State<DatabaseConnection>,
+    Extension(session): Extension<AuthSession>,
+    Path(id): Path<i32>,
+) -> Result<Json<pending_resolution::Model>, (StatusCode, String)> {
+    let pending = find_pending(&db, id).await?;
+    require_awaiting(&pending)?;
+
+    let abandoned = transition(
+        &db,
+        id,
+        ResolutionStatus::AwaitingSignature,
+        ResolutionStatus::Abandoned,
+        pending.last_error,
+    )
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if !abandoned {
+        return Err((
+            StatusCode::CONFLICT,
+            format!("Resolution {} is no longer awaiting signatures", id),
+        ));
+    }
+    let updated = find_pending(&db, id).await?;
+
+    audit::record(
+        &db,
+        AuditEntry::new(session.address, "abandon_resolution")
+            .contract(updated.contract_id)
+            .market(&updated.market_address),
+    )
+    .await;
+
+    Ok(Json(updated))
+}
@@ -0,0 +1,371 @@
+use crate::audit::{self, AuditEntry};
+use crate::auth::AuthSession;
+use crate::entities::contract;
+use crate::entities::market_resolver::{self, ResolverStatus};
+use crate::handlers::oracle;
+use crate::resolver::{self, ResolverConfig};
+use crate::validation::{self, ValidationErrors};
+use axum::{
+    extract::{Extension, Json, Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, Set,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct CreateResolverRequest {
+    pub market_id: String,
+    /// RFC 3339 or `YYYY-MM-DD`; the resolver is not evaluated before this
+    pub evaluate_at: String,
+    pub config: ResolverConfig,
+}
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct ConfirmResolverRequest {
+    pub winner: u8,
+    pub source_url: Option<String>,
+    pub notes: Option<String>,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListResolverParams {
+    pub status: Option<ResolverStatus>,
+    pub market_id: Option<String>,
+}
+
+fn now() -> String {
+    chrono::Utc::now().to_rfc3339()
+}
+
+fn is_http_url(url: &str) -> bool {
+    (url.starts_with("https://") || url.starts_with("http://"))
+        && !url.contains(char::is_whitespace)
+}
+
+async fn find_resolver(
+    db: &DatabaseConnection,
+    id: i32,
+) -> Result<market_resolver::Model, (StatusCode, String)> {
+    market_resolver::Entity::find_by_id(id)
+        .one(db)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, "Resolver not found".to_string()))
+}
+
+fn require_active(model: &market_resolver::Model) -> Result<(), (StatusCode, String)> {
+    if model.status != ResolverStatus::Active {
+        return Err((
+            StatusCode::CONFLICT,
+            format!("Resolver {} is {:?}", model.id, model.status),
+        ));
+    }
+    Ok(())
+}
+
+/// Automated resolvers, newest first
+#[utoipa::path(
+    get,
+    path = "/oracle/resolvers",
+    security(("bearer" = [])),
+    tag = "oracle",
+    params(ListResolverParams),
+    responses(
+        (status = 200, description = "Configured resolvers", body = [market_resolver::Model]),
+        (status = 401, description = "Missing or invalid session", body = String),
+        (status = 403, description = "Caller lacks the required role", body = String),
+    )
+)]
+pub async fn list_resolvers(
+    State(db): State<DatabaseConnection>,
+    Query(params): Query<ListResolverParams>,
+) -> Result<Json<Vec<market_resolver::Model>>, (StatusCode, String)> {
+    let mut query = market_resolver::Entity::find().order_by_desc(market_resolver::Column::Id);
+    if let Some(status) = params.status {
+        query = query.filter(market_resolver::Column::Status.eq(status));
+    }
+    if let Some(market_id) = params.market_id {
+        query = query.filter(market_resolver::Column::MarketAddress.eq(market_id));
+    }
+
+    query
+        .all(&db)
+        .await
+        .map(Json)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+/// Configure the automated resolver of a registered market
+#[utoipa::path(
+    post,
+    path = "/oracle/resolvers",
+    security(("bearer" = [])),
+    tag = "oracle",
+    request_body = CreateResolverRequest,
+    responses(
+        (status = 200, description = "Resolver created", body = market_resolver::Model),
+        (status = 400, description = "Invalid fields", body = ValidationErrors),
+        (status = 404, description = "Market not registered", body = String),
+        (status = 409, description = "Market is settled or already has an active resolver", body = String),
+        (status = 401, description = "Missing or invalid session", body = String),
+        (status = 403, description = "Caller lacks the required role", body = String),
+    )
+)]
+pub async fn create_resolver(
+    State(db): State<DatabaseConnection>,
+    Extension(session): Extension<AuthSession>,
+    Json(payload): Json<CreateResolverRequest>,
+) -> Result<Json<market_resolver::Model>, Response> {
+    let market = contract::Entity::find()
+        .filter(contract::Column::Address.eq(&payload.market_id))
+        .one(&db)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response())?
+        .ok_or_else(|| {
+            (StatusCode::NOT_FOUND, "Market not registered".to_string()).into_response()
+        })?;
+    if market.resolved || market.cancelled {
+        return Err((
+            StatusCode::CONFLICT,
+            format!("Market {} is already settled", market.address),
+        )
+            .into_response());
+    }
+    let active = resolver::active_for(&db, &market.address)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response())?;
+    if let Some(existing) = active {
+        return Err((
+            StatusCode::CONFLICT,
+            format!(
+                "Resolver {} for this market is active; disable it first",
+                existing.id
+            ),
+        )
+            .into_response());
+    }
+
+    let mut errors = ValidationErrors::new();
+    let evaluate_at = validation::parse_end_date(payload.evaluate_at.trim());
+    if evaluate_at.is_none() {
+        errors.add("evaluate_at", "must be RFC 3339 or YYYY-MM-DD");
+    }
+    let options = oracle::option_labels(&market).len();
+    for winner in payload.config.winners() {
+        if winner as usize >= options {
+            errors.add(
+                "config",
+                format!(
+                    "winner {} is out of range; the market has {} options",
+                    winner, options
+                ),
+            );
+        }
+    }
+    match &payload.config {
+        ResolverConfig::HttpThreshold(http) => {
+            if !is_http_url(&http.url) {
+                errors.add("config.url", "must be an http(s) URL");
+            } else if let Err(e) = resolver::check_public_url(&http.url).await {
+                errors.add("config.url", e);
+            }
+            if !http.pointer.is_empty() && !http.pointer.starts_with('/') {
+                errors.add(
+                    "config.pointer",
+                    "must be a JSON pointer such as /data/price",
+                );
+            }
+            if !http.threshold.is_finite() {
+                errors.add("config.threshold", "must be a finite number");
+            }
+        }
+        ResolverConfig::ScheduledManual(manual) => {
+            if manual
+                .source_url
+                .as_deref()
+                .is_some_and(|u| !is_http_url(u))
+            {
+                errors.add("config.source_url", "must be an http(s) URL");
+            }
+        }
+    }
+    let evaluate_at = match evaluate_at {
+        Some(at) if errors.is_empty() => at,
+        _ => return Err(errors.into_response()),
+    };
+
+    let config = serde_json::to_string(&payload.config)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response())?;
+    let inserted = market_resolver::ActiveModel {
+        contract_id: Set(market.id),
+        market_address: Set(market.address.clone()),
+        kind: Set(payload.config.kind().to_string()),
+        config: Set(config),
+        status: Set(ResolverStatus::Active),
+        evaluate_at: Set(evaluate_at.with_timezone(&chrono::Utc).to_rfc3339()),
+        last_checked_at: Set(None),
+        last_error: Set(None),
+        job_id: Set(None),
+        created_by: Set(session.address.to_string()),
+        created_at: Set(now()),
+        updated_at: Set(now()),
+        ..Default::default()
+    }
+    .insert(&db)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response())?;
+
+    audit::record(
+        &db,
+        AuditEntry::new(session.address, "create_resolver")
+            .contract(Some(inserted.contract_id))
+            .market(&inserted.market_address)
+            .payload(&payload),
+    )
+    .await;
+
+    Ok(Json(inserted))
+}
+
+/// Confirm the outcome of a `scheduled_manual` resolver; it resolves the
+/// market once `evaluate_at` has passed
+#[utoipa::path(
+    post,
+    path = "/oracle/resolvers/{id}/confirm",
+    security(("bearer" = [])),
+    tag = "oracle",
+    params(("id" = i32, Path, description = "Resolver id")),
+    request_body = ConfirmResolverRequest,
+    responses(
+        (status = 200, description = "Outcome confirmed", body = market_resolver::Model),
+        (status = 400, description = "Invalid fields, or not a scheduled_manual resolver", body = ValidationErrors),
+        (status = 404, description = "Resolver not found", body = String),
+        (status = 409, description = "Resolver is no longer active", body = String),
+        (status = 401, description = "Missing or invalid session", body = String),
+        (status = 403, description = "Caller lacks the required role", body = String),
+    )
+)]
+pub async fn confirm_resolver(
+    State(db): State<DatabaseConnection>,
+    Extension(session): Extension<AuthSession>,
+    Path(id): Path<i32>,
+    Json(payload): Json<ConfirmResolverRequest>,
+) -> Result<Json<market_resolver::Model>, Response> {
+    let model = find_resolver(&db, id)
+        .await
+        .map_err(IntoResponse::into_response)?;
+    require_active(&model).map_err(IntoResponse::into_response)?;
+
+    let mut errors = ValidationErrors::new();
+    let mut manual = match serde_json::from_str(&model.config) {
+        Ok(ResolverConfig::ScheduledManual(manual)) => manual,
+        _ => {
+            errors.add("id", format!("resolver {} is not scheduled_manual", id));
+            return Err(errors.into_response());
+        }
+    };
+    let market = contract::Entity::find_by_id(model.contract_id)
+        .one(&db)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response())?;
+    let options = market
+        .as_ref()
+        .map_or(0, |m| oracle::option_labels(m).len());
+    if payload.winner as usize >= options {
+        errors.add(
+            "winner",
+            format!(
+                "winner {} is out of range; the market has {} options",
+                payload.winner, options
+            ),
+        );
+    }
+    let source_url = payload
+        .source_url
+        .as_deref()
+        .map(str::trim)
+        .filter(|u| !u.is_empty());
+    if source_url.is_some_and(|u| !is_http_url(u)) {
+        errors.add("source_url", "must be an http(s) URL");
+    }
+    errors
+        .into_result(())
+        .map_err(IntoResponse::into_response)?;
+
+    manual.confirmed_winner = Some(payload.winner);
+    manual.confirmed_by = Some(session.address.to_string());
+    if source_url.is_some() {
+        manual.source_url = source_url.map(str::to_string);
+    }
+    if let Some(notes) = payload.notes.as_deref().map(str::trim) {
+        manual.notes = Some(notes.to_string()).filter(|n| !n.is_empty());
+    }
+    let config = serde_json::to_string(&ResolverConfig::ScheduledManual(manual))
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response())?;
+
+    let mut active: market_resolver::ActiveModel = model.into();
+    active.config = Set(config);
+    active.updated_at = Set(now());
+    let updated = active
+        .update(&db)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response())?;
+
+    audit::record(
+        &db,
+        AuditEntry::new(session.address, "confirm_resolver")
+            .contract(Some(updated.contract_id))
+            .market(&updated.market_address)
+            .payload(&payload),
+    )
+    .await;
+
+    Ok(Json(updated))
+}
+
+/// Stop an active resolver; the market can then be resolved manually or get a new resolver
+#[utoipa::path(
+    delete,
+    path = "/oracle/resolvers/{id}",
+    security(("bearer" = [])),
+    tag = "oracle",
+    params(("id" = i32, Path, description = "Resolver id")),
+    responses(
+        (status = 200, description = "Disabled resolver", body = market_resolver::Model),
+        (status = 404, description = "Resolver not found", body = String),
+        (status = 409, description = "Resolver is no longer active", body = String),
+        (status = 401, description = "Missing or invalid session", body = String),
+        (status = 403, description = "Caller lacks the required role", body = String),
+    )
+)]
+pub async fn disable_resolver(
+    State(db): State<DatabaseConnection>,
+    Extension(session): Extension<AuthSession>,
+    Path(id): Path<i32>,
+) -> Result<Json<market_resolver::Model>, (StatusCode, String)> {
+    let model = find_resolver(&db, id).await?;
+    require_active(&model)?;
+
+    let mut active: market_resolver::ActiveModel = model.into();
+    active.status = Set(ResolverStatus::Disabled);
+    active.updated_at = Set(now());
+    let updated = active
+        .update(&db)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    audit::record(
+        &db,
+        AuditEntry::new(session.address, "disable_resolver")
+            .contract(Some(updated.contract_id))
+            .market(&updated.market_address),
+    )
+    .await;
+
+    Ok(Json(updated))
+}
@@ -0,0 +1,153 @@
+use crate::auth::AuthSession;
+use crate::chain;
+use crate::sponsor::{self, SponsorConfig, SponsorQuota};
+use crate::tx::{Executor, GasBudgetConfig, TxError};
+use axum::{
+    extract::{Extension, Json, State},
+    http::StatusCode,
+};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use sea_orm::DatabaseConnection;
+use serde::{Deserialize, Serialize};
+use sui_sdk::types::transaction::{TransactionDataAPI, TransactionKind};
+use utoipa::ToSchema;
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct SponsorRequest {
+    /// Base64 BCS `TransactionKind` built by the wallet (e.g. `tx.build({ onlyTransactionKind: true })`)
+    pub tx_kind: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct SponsorResponse {
+    /// Base64 BCS `TransactionData` with sponsor gas attached; the wallet signs these bytes
+    pub tx_bytes: String,
+    /// Base64 sponsor signature, submitted together with the wallet's
+    pub sponsor_signature: String,
+    pub digest: String,
+    pub gas_budget: u64,
+    /// The sponsor gas coin may be reused after this; submit before then
+    pub expires_at: String,
+    pub quota: SponsorQuota,
+}
+
+fn enabled(config: &SponsorConfig) -> Result<(), (StatusCode, String)> {
+    if config.daily_tx_limit == 0 {
+        return Err((
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Gas sponsorship is disabled".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Attach sponsor gas to a `place_bet` / `claim_reward` transaction of the signed-in wallet
+#[utoipa::path(
+    post,
+    path = "/sponsor",
+    security(("bearer" = [])),
+    tag = "sponsor",
+    request_body = SponsorRequest,
+    responses(
+        (status = 200, description = "Sponsor-signed transaction; sign `tx_bytes` and submit both signatures", body = SponsorResponse),
+        (status = 400, description = "Malformed transaction or calls outside the sponsored functions", body = String),
+        (status = 401, description = "Missing or invalid session", body = String),
+        (status = 422, description = "Transaction would fail or exceed the per-transaction gas cap", body = String),
+        (status = 429, description = "Wallet's daily sponsorship quota is used up", body = String),
+        (status = 502, description = "Fullnode unreachable", body = String),
+        (status = 503, description = "Sponsorship disabled", body = String),
+    )
+)]
+pub async fn sponsor_transaction(
+    State(db): State<DatabaseConnection>,
+    Extension(session): Extension<AuthSession>,
+    Json(payload): Json<SponsorRequest>,
+) -> Result<Json<SponsorResponse>, (StatusCode, String)> {
+    let config = SponsorConfig::from_env();
+    enabled(&config)?;
+
+    let kind: TransactionKind = BASE64
+        .decode(payload.tx_kind.trim())
+        .map_err(|e| e.to_string())
+        .and_then(|bytes| bcs::from_bytes(&bytes).map_err(|e| e.to_string()))
+        .map_err(|e| {
+            (
+                StatusCode::BAD_REQUEST,
+                format!("Invalid transaction kind: {}", e),
+            )
+        })?;
+    let package_id = chain::package_id().map_err(TxError::Config)?;
+    let functions =
+        sponsor::check_kind(&kind, package_id).map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+
+    let reservation = sponsor::reserve(&db, session.address, &functions, &config).await?;
+
+    let budget = GasBudgetConfig {
+        max: config.max_gas_budget,
+        ..GasBudgetConfig::from_env()
+    };
+    let sponsored = match Executor::from_env().await {
+        Ok(executor) => executor.sponsor(kind, session.address, &budget).await,
+        Err(e) => Err(e),
+    };
+    let (tx_data, signature, lease) = match sponsored {
+        Ok(sponsored) => sponsored,
+        Err(e) => {
+            sponsor::settle(&db, reservation, None).await?;
+            return Err(e.into());
+        }
+    };
+
+    let tx_bytes = bcs::to_bytes(&tx_data).map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to serialize transaction: {}", e),
+        )
+    })?;
+    let digest = tx_data.digest().to_string();
+    let gas_budget = tx_data.gas_data().budget;
+    sponsor::settle(&db, reservation, Some((digest.clone(), gas_budget))).await?;
+
+    // The wallet submits on its own; keep the coin out of backend transactions meanwhile
+    lease.keep_for(config.coin_hold);
+    let expires_at =
+        chrono::Utc::now() + chrono::Duration::seconds(config.coin_hold.as_secs() as i64);
+
+    println!(
+        "Sponsor: {} {} for {} (budget {})",
+        functions.join(","),
+        digest,
+        session.address,
+        gas_budget
+    );
+
+    Ok(Json(SponsorResponse {
+        tx_bytes: BASE64.encode(tx_bytes),
+        sponsor_signature: BASE64.encode(signature.as_ref()),
+        digest,
+        gas_budget,
+        expires_at: expires_at.to_rfc3339(),
+        quota: sponsor::quota(&db, session.address, &config).await?,
+    }))
+}
+
+/// Sponsorship usage of the signed-in wallet over the last 24 hours
+#[utoipa::path(
+    get,
+    path = "/sponsor/quota",
+    security(("bearer" = [])),
+    tag = "sponsor",
+    responses(
+        (status = 200, description = "Limits and usage", body = SponsorQuota),
+        (status = 401, description = "Missing or invalid session", body = String),
+    )
+)]
+pub async fn get_quota(
+    State(db): State<DatabaseConnection>,
+    Extension(session): Extension<AuthSession>,
+) -> Result<Json<SponsorQuota>, (StatusCode, String)> {
+    let config = SponsorConfig::from_env();
+    sponsor::quota(&db, session.address, &config)
+        .await
+        .map(Json)
+}
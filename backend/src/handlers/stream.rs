@@ -0,0 +1,113 @@
+//! Server-Sent Events push channel.
+//! The indexer publishes market updates on a broadcast bus; each `/stream`
+//! client receives them, optionally filtered to the markets it subscribed to.
+
+use axum::{
+    extract::Query,
+    response::sse::{Event, KeepAlive, Sse},
+};
+use serde::{Deserialize, Serialize};
+use std::{convert::Infallible, time::Duration};
+use tokio::sync::broadcast;
+use tokio_stream::{wrappers::BroadcastStream, Stream, StreamExt};
+use utoipa::{IntoParams, ToSchema};
+
+/// Sender half of the market event bus, shared via `axum::Extension`
+pub type EventBus = broadcast::Sender<StreamEvent>;
+
+#[derive(Clone, Debug, Serialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StreamEvent {
+    /// A new `market_history` point was recorded
+    History {
+        contract_id: i32,
+        timestamp: String,
+        option_prices: Vec<f64>,
+        total_volume: f64,
+    },
+    /// A `BetPlaced` event was observed on-chain
+    Bet {
+        contract_id: i32,
+        better: String,
+        outcome: u8,
+        amount: u64,
+        tx_digest: String,
+    },
+    /// A `MarketResolved` event was observed on-chain
+    Resolved {
+        contract_id: i32,
+        winner: u8,
+        tx_digest: String,
+    },
+    /// A `MarketCancelled` event was observed on-chain
+    Cancelled { contract_id: i32, tx_digest: String },
+}
+
+impl StreamEvent {
+    pub fn contract_id(&self) -> i32 {
+        match self {
+            StreamEvent::History { contract_id, .. }
+            | StreamEvent::Bet { contract_id, .. }
+            | StreamEvent::Resolved { contract_id, .. }
+            | StreamEvent::Cancelled { contract_id, .. } => *contract_id,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            StreamEvent::History { .. } => "history",
+            StreamEvent::Bet { .. } => "bet",
+            StreamEvent::Resolved { .. } => "resolved",
+            StreamEvent::Cancelled { .. } => "cancelled",
+        }
+    }
+}
+
+/// Publish an event; having no subscribers is not an error
+pub fn publish(bus: &EventBus, event: StreamEvent) {
+    let _ = bus.send(event);
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct StreamParams {
+    /// Comma-separated contract ids to subscribe to; all markets when omitted
+    pub markets: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/stream",
+    tag = "stream",
+    params(StreamParams),
+    responses(
+        (status = 200, description = "text/event-stream of market events", content_type = "text/event-stream", body = StreamEvent),
+    )
+)]
+pub async fn stream_events(
+    axum::Extension(bus): axum::Extension<EventBus>,
+    Query(params): Query<StreamParams>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let filter: Option<Vec<i32>> = params.markets.map(|s| {
+        s.split(',')
+            .filter_map(|id| id.trim().parse::<i32>().ok())
+            .collect()
+    });
+
+    // Lagged receivers just skip the missed events; clients refetch on reconnect anyway
+    let stream = BroadcastStream::new(bus.subscribe()).filter_map(move |msg| {
+        let event = msg.ok()?;
+        if let Some(ids) = &filter {
+            if !ids.contains(&event.contract_id()) {
+                return None;
+            }
+        }
+        let sse = Event::default()
+            .event(event.name())
+            .json_data(&event)
+            .ok()?;
+        Some(Ok(sse))
+    });
+
+    Sse::new(stream).keep_alive(KeepAlive::new().interval(Duration::from_secs(15)))
+}
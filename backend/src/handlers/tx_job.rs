@@ -0,0 +1,113 @@
+use crate::entities::tx_job::{self, JobStatus};
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use sea_orm::{
+    ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+const DEFAULT_PAGE_SIZE: u64 = 50;
+const MAX_PAGE_SIZE: u64 = 200;
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct TxJobParams {
+    /// Zero-based page index
+    pub page: Option<u64>,
+    pub page_size: Option<u64>,
+    pub status: Option<JobStatus>,
+    /// create_market, resolve_market or cancel_market
+    pub kind: Option<String>,
+    pub market_address: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct TxJobPage {
+    pub items: Vec<tx_job::Model>,
+    pub page: u64,
+    pub page_size: u64,
+    pub total: u64,
+}
+
+/// Newest-first backend transaction jobs
+#[utoipa::path(
+    get,
+    path = "/admin/jobs",
+    security(("bearer" = [])),
+    tag = "admin",
+    params(TxJobParams),
+    responses(
+        (status = 200, description = "One page of transaction jobs", body = TxJobPage),
+        (status = 401, description = "Missing or invalid session", body = String),
+        (status = 403, description = "Caller lacks the required role", body = String),
+    )
+)]
+pub async fn list_jobs(
+    State(db): State<DatabaseConnection>,
+    Query(params): Query<TxJobParams>,
+) -> Result<Json<TxJobPage>, (StatusCode, String)> {
+    let page = params.page.unwrap_or(0);
+    let page_size = params
+        .page_size
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+
+    let mut query = tx_job::Entity::find().order_by_desc(tx_job::Column::Id);
+
+    if let Some(status) = params.status {
+        query = query.filter(tx_job::Column::Status.eq(status));
+    }
+    if let Some(kind) = params.kind {
+        query = query.filter(tx_job::Column::Kind.eq(kind));
+    }
+    if let Some(market_address) = params.market_address {
+        query = query.filter(tx_job::Column::MarketAddress.eq(market_address));
+    }
+
+    let paginator = query.paginate(&db, page_size);
+    let total = paginator
+        .num_items()
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let items = paginator
+        .fetch_page(page)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(TxJobPage {
+        items,
+        page,
+        page_size,
+        total,
+    }))
+}
+
+/// Current state of one transaction job; poll after a 202 response
+#[utoipa::path(
+    get,
+    path = "/admin/jobs/{id}",
+    security(("bearer" = [])),
+    tag = "admin",
+    params(("id" = i32, Path, description = "Job id")),
+    responses(
+        (status = 200, description = "Transaction job", body = tx_job::Model),
+        (status = 404, description = "Job not found", body = String),
+        (status = 401, description = "Missing or invalid session", body = String),
+        (status = 403, description = "Caller lacks the required role", body = String),
+    )
+)]
+pub async fn get_job(
+    State(db): State<DatabaseConnection>,
+    Path(id): Path<i32>,
+) -> Result<Json<tx_job::Model>, (StatusCode, String)> {
+    tx_job::Entity::find_by_id(id)
+        .one(&db)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .map(Json)
+        .ok_or((StatusCode::NOT_FOUND, "Job not found".to_string()))
+}
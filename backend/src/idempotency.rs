@@ -0,0 +1,144 @@
+//! `Idempotency-Key` support for requests that create on-chain state.
+//!
+//! The key is stored with what the request produced (a contract row or a
+//! transaction job), so a repeat returns the original result. A key is bound to
+//! the wallet and request body that first used it; reusing it for anything else
+//! is a 422. While a request is running it holds a lease on the key's row, so a
+//! concurrent duplicate gets a 409 instead of racing the first one, also on
+//! other backend instances sharing the database.
+
+use crate::entities::idempotency_key;
+use axum::http::{HeaderMap, StatusCode};
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait,
+    QueryFilter, Set,
+};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::time::Duration;
+
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+const MAX_KEY_LEN: usize = 255;
+
+// Outlives any request; only matters when an instance dies holding a key
+const LEASE: Duration = Duration::from_secs(300);
+
+/// The request's key, if any; blank keys count as absent
+pub fn from_headers(headers: &HeaderMap) -> Result<Option<String>, (StatusCode, String)> {
+    let Some(value) = headers.get(IDEMPOTENCY_KEY_HEADER) else {
+        return Ok(None);
+    };
+    let key = value
+        .to_str()
+        .map_err(|_| {
+            (
+                StatusCode::BAD_REQUEST,
+                "Idempotency-Key must be visible ASCII".to_string(),
+            )
+        })?
+        .trim();
+    if key.len() > MAX_KEY_LEN {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("Idempotency-Key must be at most {} characters", MAX_KEY_LEN),
+        ));
+    }
+    Ok(Some(key.to_string()).filter(|k| !k.is_empty()))
+}
+
+/// SHA-256 of the request's JSON form, compared when a key is reused
+pub fn request_hash<T: Serialize>(request: &T) -> String {
+    let bytes = serde_json::to_vec(request).unwrap_or_default();
+    format!("{:x}", Sha256::digest(&bytes))
+}
+
+/// Key claimed by a running request; released on drop
+pub struct KeyClaim {
+    db: DatabaseConnection,
+    key: String,
+}
+
+impl Drop for KeyClaim {
+    fn drop(&mut self) {
+        let db = self.db.clone();
+        let key = std::mem::take(&mut self.key);
+        tokio::spawn(async move {
+            if let Err(e) = idempotency_key::Entity::update_many()
+                .col_expr(idempotency_key::Column::ClaimedUntilMs, Expr::value(0i64))
+                .filter(idempotency_key::Column::Key.eq(&key))
+                .exec(&db)
+                .await
+            {
+                eprintln!("Idempotency: Failed to release key {}: {}", key, e);
+            }
+        });
+    }
+}
+
+/// Claim `key` for a request by `actor` with body hash `request_hash`.
+/// 422 when the key was used for a different request, 409 while another
+/// request with it is still running.
+pub async fn claim(
+    db: &DatabaseConnection,
+    key: &str,
+    actor: &str,
+    request_hash: &str,
+) -> Result<KeyClaim, (StatusCode, String)> {
+    let db_error = |e: DbErr| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string());
+    let now_ms = chrono::Utc::now().timestamp_millis();
+    let lease_until = now_ms + LEASE.as_millis() as i64;
+    let claimed = || KeyClaim {
+        db: db.clone(),
+        key: key.to_string(),
+    };
+
+    // The UNIQUE key column lets exactly one first request in
+    let inserted = idempotency_key::ActiveModel {
+        key: Set(key.to_string()),
+        actor: Set(actor.to_string()),
+        request_hash: Set(request_hash.to_string()),
+        claimed_until_ms: Set(lease_until),
+        created_at: Set(chrono::Utc::now().to_rfc3339()),
+        ..Default::default()
+    }
+    .insert(db)
+    .await;
+    let insert_error = match inserted {
+        Ok(_) => return Ok(claimed()),
+        Err(e) => e,
+    };
+
+    let existing = idempotency_key::Entity::find()
+        .filter(idempotency_key::Column::Key.eq(key))
+        .one(db)
+        .await
+        .map_err(db_error)?;
+    let Some(existing) = existing else {
+        return Err(db_error(insert_error));
+    };
+    if existing.actor != actor || existing.request_hash != request_hash {
+        return Err((
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "Idempotency-Key was already used for a different request".to_string(),
+        ));
+    }
+
+    // Take over the lease only once the earlier request has let go of it
+    let taken = idempotency_key::Entity::update_many()
+        .col_expr(
+            idempotency_key::Column::ClaimedUntilMs,
+            Expr::value(lease_until),
+        )
+        .filter(idempotency_key::Column::Key.eq(key))
+        .filter(idempotency_key::Column::ClaimedUntilMs.lt(now_ms))
+        .exec(db)
+        .await
+        .map_err(db_error)?;
+    if taken.rows_affected == 0 {
+        return Err((
+            StatusCode::CONFLICT,
+            "A request with this Idempotency-Key is still in progress".to_string(),
+        ));
+    }
+    Ok(claimed())
+}
@@ -0,0 +1,696 @@
+//! Persistent queue for backend-signed transactions.
+//!
+//! Every admin transaction is recorded in `tx_jobs` before it is signed, and
+//! its digest is stored before it is sent; offline resolutions signed by their
+//! oracle are recorded with their signed bytes. When sending fails in a way
+//! that leaves the outcome unknown (timeouts, fullnode errors), the job stays
+//! `submitted` and the tx job worker reconciles it by digest, resending the
+//! identical signed transaction until it lands or attempts run out.
+
+use crate::audit::{self, AuditEntry};
+use crate::chain::MarketCreatedEvent;
+use crate::entities::pending_resolution::ResolutionStatus;
+use crate::entities::tx_job::{self, JobStatus};
+use crate::fees;
+use crate::gas;
+use crate::handlers::{cancel, contract, oracle, resolution};
+use crate::tx::{self, Executor, MoveCall, SignedTx, TxError, TxOutcome};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, Set,
+    SqlErr,
+};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use sui_sdk::{
+    json::SuiJsonValue,
+    types::{
+        base_types::{ObjectID, SuiAddress},
+        transaction::Transaction,
+    },
+};
+
+/// Sends of one signed transaction before its job is given up as failed
+const MAX_ATTEMPTS: i32 = 5;
+/// Minimum age of a submitted job before the worker reconciles it
+const RETRY_DELAY_SECS: i64 = 15;
+/// Minimum age of a pending job before the worker assumes its request died
+const STALE_PENDING_SECS: i64 = 60;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum JobSpec {
+    CreateMarket {
+        question: String,
+        options_count: u8,
+        end_time_ms: u64,
+        oracle: Option<String>,
+        /// Stored as a contract row once the transaction confirms
+        listing: contract::NewListing,
+    },
+    ResolveMarket {
+        market_id: String,
+        winner: u8,
+        /// Stored with the resolution record once the transaction confirms
+        #[serde(default)]
+        source: oracle::ResolutionSource,
+    },
+    CancelMarket {
+        market_id: String,
+    },
+    /// An offline resolution signed by the market's oracle, see `resolution`
+    SubmitResolution {
+        resolution_id: i32,
+        market_id: String,
+        winner: u8,
+        /// The oracle that signed; recorded as the resolver
+        sender: String,
+        #[serde(default)]
+        source: oracle::ResolutionSource,
+    },
+    /// One `withdraw_platform_fees` call per market, in a single transaction
+    WithdrawFees {
+        markets: Vec<fees::Withdrawable>,
+    },
+}
+
+impl JobSpec {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            JobSpec::CreateMarket { .. } => "create_market",
+            JobSpec::ResolveMarket { .. } | JobSpec::SubmitResolution { .. } => "resolve_market",
+            JobSpec::CancelMarket { .. } => "cancel_market",
+            JobSpec::WithdrawFees { .. } => "withdraw_platform_fees",
+        }
+    }
+
+    fn idempotency_key(&self) -> Option<&str> {
+        match self {
+            JobSpec::CreateMarket { listing, .. } => listing.idempotency_key.as_deref(),
+            _ => None,
+        }
+    }
+
+    fn market_address(&self) -> Option<&str> {
+        match self {
+            JobSpec::CreateMarket { .. } | JobSpec::WithdrawFees { .. } => None,
+            JobSpec::ResolveMarket { market_id, .. }
+            | JobSpec::SubmitResolution { market_id, .. }
+            | JobSpec::CancelMarket { market_id } => Some(market_id),
+        }
+    }
+
+    fn calls(&self, sender: SuiAddress) -> Result<Vec<MoveCall>, TxError> {
+        match self {
+            JobSpec::CreateMarket {
+                question,
+                options_count,
+                end_time_ms,
+                oracle,
+                ..
+            } => {
+                let oracle = oracle
+                    .as_deref()
+                    .map(|o| o.parse::<SuiAddress>())
+                    .transpose()
+                    .map_err(|e| TxError::Build(format!("Invalid oracle: {}", e)))?;
+                let (call, _) = contract::create_market_call(
+                    sender,
+                    question,
+                    *options_count,
+                    *end_time_ms,
+                    oracle,
+                )?;
+                Ok(vec![call])
+            }
+            JobSpec::ResolveMarket {
+                market_id, winner, ..
+            } => Ok(vec![oracle::resolve_call(market_id, *winner)?]),
+            JobSpec::CancelMarket { market_id } => Ok(vec![cancel::cancel_call(market_id)?]),
+            JobSpec::SubmitResolution { .. } => Err(TxError::Build(
+                "Offline resolutions are signed by their oracle, not the backend".to_string(),
+            )),
+            JobSpec::WithdrawFees { markets } => markets
+                .iter()
+                .map(|m| {
+                    let market_id = ObjectID::from_str(&m.market_id)
+                        .map_err(|e| TxError::Build(format!("Invalid Market ID: {}", e)))?;
+                    Ok(MoveCall::new(
+                        "withdraw_platform_fees",
+                        vec![SuiJsonValue::from_object_id(market_id)],
+                    ))
+                })
+                .collect(),
+        }
+    }
+}
+
+pub enum JobRun {
+    Confirmed {
+        job: tx_job::Model,
+        outcome: TxOutcome,
+    },
+    /// Sent, but the outcome is not known yet; poll the job
+    Pending(tx_job::Model),
+}
+
+fn now() -> String {
+    chrono::Utc::now().to_rfc3339()
+}
+
+/// The pending or submitted job for a market, if any
+pub async fn open_job_for(db: &DatabaseConnection, market_address: &str) -> Option<tx_job::Model> {
+    tx_job::Entity::find()
+        .filter(tx_job::Column::MarketAddress.eq(market_address))
+        .filter(tx_job::Column::Status.is_in([JobStatus::Pending, JobStatus::Submitted]))
+        .one(db)
+        .await
+        .ok()
+        .flatten()
+}
+
+async fn open_job_of_kind(db: &DatabaseConnection, kind: &str) -> Option<tx_job::Model> {
+    tx_job::Entity::find()
+        .filter(tx_job::Column::Kind.eq(kind))
+        .filter(tx_job::Column::Status.is_in([JobStatus::Pending, JobStatus::Submitted]))
+        .one(db)
+        .await
+        .ok()
+        .flatten()
+}
+
+/// Audit an action refused before any job was recorded, e.g. by pre-flight checks
+pub async fn reject(
+    db: &DatabaseConnection,
+    actor: impl ToString,
+    spec: &JobSpec,
+    error: TxError,
+) -> TxError {
+    let mut entry = AuditEntry::new(actor, spec.kind())
+        .payload(spec)
+        .error(Some(error.to_string()));
+    if let Some(market) = spec.market_address() {
+        entry = entry
+            .contract(audit::contract_id_for(db, market).await)
+            .market(market);
+    }
+    audit::record(db, entry).await;
+    error
+}
+
+/// Refuse a job while another transaction for its market (or another fee
+/// withdrawal) has not finished
+async fn check_in_flight(db: &DatabaseConnection, spec: &JobSpec) -> Result<(), TxError> {
+    if let Some(market) = spec.market_address() {
+        if let Some(open) = open_job_for(db, market).await {
+            return Err(TxError::InFlight(format!(
+                "Job {} ({}) for market {} has not finished",
+                open.id, open.kind, market
+            )));
+        }
+        if let Some(pending) = resolution::awaiting_for(db, market).await {
+            return Err(TxError::InFlight(format!(
+                "Offline resolution {} for market {} is awaiting signatures",
+                pending.id, market
+            )));
+        }
+    }
+    // Concurrent withdrawals would abort on the already emptied balances
+    if let JobSpec::WithdrawFees { .. } = spec {
+        if let Some(open) = open_job_of_kind(db, spec.kind()).await {
+            return Err(TxError::InFlight(format!(
+                "Fee withdrawal job {} has not finished",
+                open.id
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Insert a job, pending or already `signed`. The partial unique indexes on
+/// open jobs settle races `check_in_flight` misses.
+async fn insert(
+    db: &DatabaseConnection,
+    actor: &str,
+    spec: &JobSpec,
+    signed: Option<&SignedTx>,
+) -> Result<tx_job::Model, TxError> {
+    let market_address = spec.market_address().map(str::to_string);
+    let contract_id = match &market_address {
+        Some(market) => audit::contract_id_for(db, market).await,
+        None => None,
+    };
+
+    tx_job::ActiveModel {
+        kind: Set(spec.kind().to_string()),
+        actor: Set(actor.to_string()),
+        contract_id: Set(contract_id),
+        market_address: Set(market_address.clone()),
+        spec: Set(serde_json::to_string(spec).unwrap_or_default()),
+        status: Set(match signed {
+            Some(_) => JobStatus::Submitted,
+            None => JobStatus::Pending,
+        }),
+        digest: Set(signed.map(|s| s.digest.clone())),
+        tx_bytes: Set(
+            signed.and_then(|s| bcs::to_bytes(&s.transaction).ok().map(|b| BASE64.encode(b)))
+        ),
+        gas_budget: Set(signed.map(|s| s.gas_budget as i64)),
+        attempts: Set(signed.map_or(0, |_| 1)),
+        idempotency_key: Set(spec.idempotency_key().map(str::to_string)),
+        created_at: Set(now()),
+        updated_at: Set(now()),
+        ..Default::default()
+    }
+    .insert(db)
+    .await
+    .map_err(|e| match e.sql_err() {
+        Some(SqlErr::UniqueConstraintViolation(_)) => TxError::InFlight(match &market_address {
+            Some(market) => format!("Another job for market {} has not finished", market),
+            None => format!("Another {} job has not finished", spec.kind()),
+        }),
+        _ => TxError::Config(format!("Failed to record job: {}", e)),
+    })
+}
+
+/// Record a job and execute it right away
+pub async fn run(
+    db: &DatabaseConnection,
+    actor: impl ToString,
+    spec: JobSpec,
+) -> Result<JobRun, TxError> {
+    let actor = actor.to_string();
+    let inserted = match check_in_flight(db, &spec).await {
+        Ok(()) => insert(db, &actor, &spec, None).await,
+        Err(e) => Err(e),
+    };
+    let job = match inserted {
+        Ok(job) => job,
+        Err(e) => return Err(reject(db, actor, &spec, e).await),
+    };
+
+    let executor = match Executor::from_env().await {
+        Ok(executor) => executor,
+        Err(e) => return fail(db, job, &spec, e).await,
+    };
+    attempt(db, &executor, job, &spec).await
+}
+
+/// Record a job for a transaction signed outside the backend and send it.
+/// It is never signed again; the worker reconciles and resends it like any
+/// other submitted job.
+pub async fn run_signed(
+    db: &DatabaseConnection,
+    actor: impl ToString,
+    spec: JobSpec,
+    signed: SignedTx,
+) -> Result<JobRun, TxError> {
+    let actor = actor.to_string();
+    let inserted = match check_in_flight(db, &spec).await {
+        Ok(()) => insert(db, &actor, &spec, Some(&signed)).await,
+        Err(e) => Err(e),
+    };
+    let job = match inserted {
+        Ok(job) => job,
+        Err(e) => return Err(reject(db, actor, &spec, e).await),
+    };
+
+    let result = match tx::connect().await {
+        Ok(client) => tx::execute(&client, &signed).await,
+        Err(e) => Err(e),
+    };
+    settle(db, job, &spec, &signed.gas_coins(), result).await
+}
+
+/// Build, sign and send a job that has no signed transaction yet
+async fn attempt(
+    db: &DatabaseConnection,
+    executor: &Executor,
+    job: tx_job::Model,
+    spec: &JobSpec,
+) -> Result<JobRun, TxError> {
+    let signed = match sign(executor, spec).await {
+        Ok(signed) => signed,
+        Err(e) => return fail(db, job, spec, e).await,
+    };
+    let coins = signed.gas_coins();
+
+    // Persist the digest before sending so a lost response can be reconciled.
+    // Only a job still pending is taken, so the request and the worker never
+    // both send one.
+    let claimed = tx_job::Entity::update_many()
+        .set(tx_job::ActiveModel {
+            status: Set(JobStatus::Submitted),
+            digest: Set(Some(signed.digest.clone())),
+            tx_bytes: Set(bcs::to_bytes(&signed.transaction)
+                .ok()
+                .map(|b| BASE64.encode(b))),
+            gas_budget: Set(Some(signed.gas_budget as i64)),
+            attempts: Set(job.attempts + 1),
+            updated_at: Set(now()),
+            ..Default::default()
+        })
+        .filter(tx_job::Column::Id.eq(job.id))
+        .filter(tx_job::Column::Status.eq(JobStatus::Pending))
+        .exec(db)
+        .await;
+    let job = match claimed {
+        Ok(claimed) if claimed.rows_affected == 1 => reload(db, job.id).await,
+        Ok(_) => {
+            gas::release(&coins);
+            return reload(db, job.id).await.map(JobRun::Pending);
+        }
+        Err(e) => Err(TxError::Config(format!("Failed to record job: {}", e))),
+    };
+    let job = match job {
+        Ok(job) => job,
+        Err(e) => {
+            gas::release(&coins);
+            return Err(e);
+        }
+    };
+
+    let result = executor.send(&signed).await;
+    settle(db, job, spec, &coins, result).await
+}
+
+async fn sign(executor: &Executor, spec: &JobSpec) -> Result<SignedTx, TxError> {
+    let calls = spec.calls(executor.sender())?;
+    let (tx_data, lease) = executor.build_batch(calls).await?;
+    let signed = executor.sign(tx_data, spec.kind()).await?;
+    // The coins stay reserved until `settle` knows the outcome
+    lease.keep();
+    Ok(signed)
+}
+
+/// Record the result of sending; transport errors leave the job for the worker
+async fn settle(
+    db: &DatabaseConnection,
+    job: tx_job::Model,
+    spec: &JobSpec,
+    coins: &[ObjectID],
+    result: Result<TxOutcome, TxError>,
+) -> Result<JobRun, TxError> {
+    match result {
+        Ok(outcome) => {
+            gas::release(coins);
+            confirm(db, job, spec, outcome).await
+        }
+        Err(TxError::Submit(e)) if job.attempts < MAX_ATTEMPTS => {
+            gas::hold(coins);
+            eprintln!("TxJob: job {} outcome unknown, will retry: {}", job.id, e);
+            let mut active: tx_job::ActiveModel = job.into();
+            active.last_error = Set(Some(e));
+            active.updated_at = Set(now());
+            let job = active
+                .update(db)
+                .await
+                .map_err(|e| TxError::Config(format!("Failed to record job: {}", e)))?;
+            Ok(JobRun::Pending(job))
+        }
+        Err(e) => {
+            gas::release(coins);
+            fail(db, job, spec, e).await
+        }
+    }
+}
+
+async fn confirm(
+    db: &DatabaseConnection,
+    job: tx_job::Model,
+    spec: &JobSpec,
+    outcome: TxOutcome,
+) -> Result<JobRun, TxError> {
+    // The request and the worker can both see the same transaction land;
+    // only the one that marks the job confirmed records its effects
+    let claimed = tx_job::Entity::update_many()
+        .set(tx_job::ActiveModel {
+            status: Set(JobStatus::Confirmed),
+            digest: Set(Some(outcome.digest.clone())),
+            updated_at: Set(now()),
+            ..Default::default()
+        })
+        .filter(tx_job::Column::Id.eq(job.id))
+        .filter(tx_job::Column::Status.ne(JobStatus::Confirmed))
+        .exec(db)
+        .await
+        .map_err(|e| TxError::Config(format!("Failed to record job: {}", e)))?;
+    if claimed.rows_affected == 0 {
+        let job = reload(db, job.id).await?;
+        return Ok(JobRun::Confirmed { job, outcome });
+    }
+
+    let mut contract_id = job.contract_id;
+    let mut market_address = job.market_address.clone();
+    let mut error = None;
+
+    if let JobSpec::CreateMarket { listing, .. } = spec {
+        match outcome.event::<MarketCreatedEvent>("MarketCreated") {
+            Some(created) => {
+                match contract::store_listing(db, listing.clone(), &created).await {
+                    Ok(model) => contract_id = Some(model.id),
+                    Err(e) => {
+                        error = Some(format!(
+                            "Market {} created but not stored: {}",
+                            created.id, e
+                        ))
+                    }
+                }
+                market_address = Some(created.id);
+            }
+            None => error = Some("No MarketCreated event in transaction".to_string()),
+        }
+    }
+    if let JobSpec::ResolveMarket {
+        market_id,
+        winner,
+        source,
+    } = spec
+    {
+        error =
+            oracle::record_resolution(db, market_id, *winner, source, &outcome.digest, &job.actor)
+                .await
+                .err();
+    }
+    if let JobSpec::SubmitResolution {
+        resolution_id,
+        market_id,
+        winner,
+        sender,
+        source,
+    } = spec
+    {
+        resolution::finish(db, *resolution_id, ResolutionStatus::Executed, None).await;
+        error = oracle::record_resolution(db, market_id, *winner, source, &outcome.digest, sender)
+            .await
+            .err();
+    }
+    if let JobSpec::WithdrawFees { markets } = spec {
+        error = fees::record_revenue(db, &job, markets, &outcome)
+            .await
+            .err();
+    }
+
+    let result = serde_json::json!({
+        "digest": outcome.digest,
+        "gas_budget": outcome.gas_budget,
+        "gas_used": outcome.gas,
+        "contract_id": contract_id,
+    });
+
+    let mut active: tx_job::ActiveModel = job.into();
+    active.contract_id = Set(contract_id);
+    active.market_address = Set(market_address.clone());
+    active.result = Set(Some(result.to_string()));
+    active.last_error = Set(error.clone());
+    active.updated_at = Set(now());
+    let job = active
+        .update(db)
+        .await
+        .map_err(|e| TxError::Config(format!("Failed to record job: {}", e)))?;
+
+    let mut entry = AuditEntry::new(&job.actor, spec.kind())
+        .contract(contract_id)
+        .payload(spec)
+        .digest(Some(outcome.digest.clone()))
+        .error(error);
+    if let Some(market) = &market_address {
+        entry = entry.market(market);
+    }
+    audit::record(db, entry).await;
+
+    Ok(JobRun::Confirmed { job, outcome })
+}
+
+async fn fail(
+    db: &DatabaseConnection,
+    job: tx_job::Model,
+    spec: &JobSpec,
+    error: TxError,
+) -> Result<JobRun, TxError> {
+    let message = error.to_string();
+    let actor = job.actor.clone();
+    let contract_id = job.contract_id;
+    let market_address = job.market_address.clone();
+    let digest = error.digest().or(job.digest.clone());
+
+    // A job the other path already confirmed stays confirmed
+    let failed = tx_job::Entity::update_many()
+        .set(tx_job::ActiveModel {
+            status: Set(JobStatus::Failed),
+            digest: Set(digest.clone()),
+            last_error: Set(Some(message.clone())),
+            updated_at: Set(now()),
+            ..Default::default()
+        })
+        .filter(tx_job::Column::Id.eq(job.id))
+        .filter(tx_job::Column::Status.is_in([JobStatus::Pending, JobStatus::Submitted]))
+        .exec(db)
+        .await;
+    match failed {
+        Ok(failed) if failed.rows_affected == 0 => return Err(error),
+        Ok(_) => {}
+        Err(e) => eprintln!("TxJob: Failed to record job failure: {}", e),
+    }
+
+    if let JobSpec::SubmitResolution { resolution_id, .. } = spec {
+        // Aborted on chain: prepare a new resolution. Otherwise the same
+        // signatures may be submitted again.
+        let status = match error.digest() {
+            Some(_) => ResolutionStatus::Failed,
+            None => ResolutionStatus::AwaitingSignature,
+        };
+        resolution::finish(db, *resolution_id, status, Some(message.clone())).await;
+    }
+
+    let mut entry = AuditEntry::new(actor, spec.kind())
+        .contract(contract_id)
+        .payload(spec)
+        .digest(digest)
+        .error(Some(message));
+    if let Some(market) = &market_address {
+        entry = entry.market(market);
+    }
+    audit::record(db, entry).await;
+
+    Err(error)
+}
+
+async fn reload(db: &DatabaseConnection, id: i32) -> Result<tx_job::Model, TxError> {
+    tx_job::Entity::find_by_id(id)
+        .one(db)
+        .await
+        .map_err(|e| TxError::Config(format!("Failed to fetch job: {}", e)))?
+        .ok_or_else(|| TxError::Config(format!("Job {} no longer exists", id)))
+}
+
+/// Resume every open job old enough to be considered stuck
+pub async fn reconcile(db: &DatabaseConnection, executor: &Executor) {
+    let jobs = match tx_job::Entity::find()
+        .filter(tx_job::Column::Status.is_in([JobStatus::Pending, JobStatus::Submitted]))
+        .order_by_asc(tx_job::Column::Id)
+        .all(db)
+        .await
+    {
+        Ok(jobs) => jobs,
+        Err(e) => {
+            eprintln!("TxJob: Failed to fetch open jobs: {}", e);
+            return;
+        }
+    };
+
+    let cutoff = |secs| (chrono::Utc::now() - chrono::Duration::seconds(secs)).to_rfc3339();
+    let (retry_cutoff, stale_cutoff) = (cutoff(RETRY_DELAY_SECS), cutoff(STALE_PENDING_SECS));
+
+    for job in jobs {
+        let signed = job.tx_bytes.as_deref().and_then(|b| decode(&job, b));
+        // Keep coins of in-flight transactions out of new ones, also across restarts
+        if let Some(signed) = &signed {
+            gas::hold(&signed.gas_coins());
+        }
+        let due = match job.status {
+            JobStatus::Pending => job.updated_at <= stale_cutoff,
+            _ => job.updated_at <= retry_cutoff,
+        };
+        if !due {
+            continue;
+        }
+
+        let spec: JobSpec = match serde_json::from_str(&job.spec) {
+            Ok(spec) => spec,
+            Err(e) => {
+                eprintln!("TxJob: job {} has an unreadable spec: {}", job.id, e);
+                continue;
+            }
+        };
+
+        let id = job.id;
+        let result = match (job.status, signed) {
+            // Recorded but never signed, e.g. the process stopped mid-request
+            (JobStatus::Pending, _) => attempt(db, executor, job, &spec).await,
+            (_, Some(signed)) => resume(db, executor, job, &spec, signed).await,
+            // Signing again could execute the call twice
+            (_, None) => {
+                let error = TxError::Submit("Signed transaction could not be decoded".to_string());
+                fail(db, job, &spec, error).await
+            }
+        };
+        match result {
+            Ok(JobRun::Confirmed { outcome, .. }) => {
+                println!("TxJob: job {} confirmed ({})", id, outcome.digest)
+            }
+            Ok(JobRun::Pending(_)) => {}
+            Err(e) => eprintln!("TxJob: job {} failed: {}", id, e),
+        }
+    }
+}
+
+fn decode(job: &tx_job::Model, tx_bytes: &str) -> Option<SignedTx> {
+    let transaction: Transaction = BASE64
+        .decode(tx_bytes)
+        .ok()
+        .and_then(|b| bcs::from_bytes(&b).ok())?;
+    Some(SignedTx {
+        digest: transaction.digest().to_string(),
+        transaction,
+        gas_budget: job.gas_budget.unwrap_or(0) as u64,
+        label: job.kind.clone(),
+    })
+}
+
+/// Check a submitted job by digest and resend the same transaction if it has not landed
+async fn resume(
+    db: &DatabaseConnection,
+    executor: &Executor,
+    job: tx_job::Model,
+    spec: &JobSpec,
+    signed: SignedTx,
+) -> Result<JobRun, TxError> {
+    let coins = signed.gas_coins();
+
+    match executor.lookup(&signed.digest, signed.gas_budget).await {
+        Ok(Some(outcome)) => return settle(db, job, spec, &coins, Ok(outcome)).await,
+        Err(e) => return settle(db, job, spec, &coins, Err(e)).await,
+        Ok(None) => {}
+    }
+
+    if job.attempts >= MAX_ATTEMPTS {
+        gas::release(&coins);
+        let error = TxError::Submit(format!(
+            "Transaction {} not found after {} attempts",
+            signed.digest, job.attempts
+        ));
+        return fail(db, job, spec, error).await;
+    }
+
+    let mut active: tx_job::ActiveModel = job.clone().into();
+    active.attempts = Set(job.attempts + 1);
+    active.updated_at = Set(now());
+    let job = active
+        .update(db)
+        .await
+        .map_err(|e| TxError::Config(format!("Failed to record job: {}", e)))?;
+
+    let result = executor.send(&signed).await;
+    settle(db, job, spec, &coins, result).await
+}
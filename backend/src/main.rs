@@ -5,18 +5,38 @@ use axum::{
     response::{IntoResponse, Response},
 };
 use axum::{
-    routing::{delete, get},
+    middleware,
+    routing::{delete, get, post},
     Router,
 };
+use entities::account::Role;
 #[cfg(not(debug_assertions))]
 use rust_embed::Embed;
+use sea_orm::DatabaseConnection;
 use std::net::SocketAddr;
-use tower_http::cors::CorsLayer;
+use utoipa::OpenApi;
+use utoipa_scalar::{Scalar, Servable};
 
+mod audit;
+mod auth;
+mod chain;
+mod cors;
 mod cron;
 mod db;
 mod entities;
+mod fees;
+mod gas;
 mod handlers;
+mod idempotency;
+mod jobs;
+mod openapi;
+mod preflight;
+mod rate_limit;
+mod resolver;
+mod signer;
+mod sponsor;
+mod tx;
+mod validation;
 
 #[cfg(not(debug_assertions))]
 #[derive(Embed)]
@@ -34,10 +54,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Channel for instant indexer triggers
     let (tx, rx) = tokio::sync::mpsc::channel::<()>(100);
 
+    // Broadcast bus for live market events (/stream)
+    let (events, _) = tokio::sync::broadcast::channel::<handlers::stream::StreamEvent>(256);
+
     // Start Indexer
     let db_clone = db.clone();
+    let events_clone = events.clone();
     tokio::spawn(async move {
-        cron::indexer::run_indexer(db_clone, rx).await;
+        cron::indexer::run_indexer(db_clone, rx, events_clone).await;
     });
 
     // Start Expired Markets Checker
@@ -46,41 +70,154 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         cron::expired_checker::run_expired_checker(db_clone2).await;
     });
 
-    // App state
-    #[allow(unused_mut)]
-    let mut app = Router::new()
-        .route(
-            "/contracts",
-            get(handlers::contract::list_contracts).post(handlers::contract::create_contract),
-        )
+    // Start Transaction Job Worker
+    let db_clone3 = db.clone();
+    tokio::spawn(async move {
+        cron::tx_jobs::run_tx_jobs(db_clone3).await;
+    });
+
+    // Start Platform Fee Withdrawal
+    let db_clone4 = db.clone();
+    tokio::spawn(async move {
+        cron::fee_withdrawal::run_fee_withdrawal(db_clone4).await;
+    });
+
+    // Start Automated Resolvers
+    let db_clone5 = db.clone();
+    tokio::spawn(async move {
+        cron::resolvers::run_resolvers(db_clone5).await;
+    });
+
+    // Start Gas Pool Maintenance
+    tokio::spawn(cron::gas_pool::run_gas_pool());
+
+    // Rate limit budgets per route group (overridable via RATE_LIMIT_<GROUP>)
+    let admin_limiter = rate_limit::RateLimiter::from_env("admin", 10, 60);
+    let wallet_limiter = rate_limit::RateLimiter::from_env("wallet", 30, 60);
+    let auth_limiter = rate_limit::RateLimiter::from_env("auth", 20, 60);
+
+    // Admin endpoints, grouped by the role they require
+    let creator_routes = Router::new()
+        .route("/contracts", post(handlers::contract::create_contract))
         .route(
             "/contracts/{id}",
             delete(handlers::contract::delete_contract),
         )
         .route(
-            "/contracts/{id}/history",
-            get(handlers::market_history::get_contract_history),
+            "/contracts/{id}/archive",
+            post(handlers::contract::archive_contract),
+        )
+        .route(
+            "/contracts/{id}/restore",
+            post(handlers::contract::restore_contract),
+        );
+    let oracle_routes = Router::new()
+        .route("/oracle/resolve", post(handlers::oracle::resolve_market))
+        .route("/market/cancel", post(handlers::cancel::cancel_market))
+        .route(
+            "/oracle/resolutions",
+            get(handlers::resolution::list_resolutions)
+                .post(handlers::resolution::prepare_resolution),
+        )
+        .route(
+            "/oracle/resolutions/{id}",
+            delete(handlers::resolution::abandon_resolution),
         )
-        .route("/categories", get(handlers::category::list_categories))
         .route(
-            "/oracle/resolve",
-            axum::routing::post(handlers::oracle::resolve_market),
+            "/oracle/resolutions/{id}/submit",
+            post(handlers::resolution::submit_resolution),
         )
+        .route(
+            "/oracle/resolvers",
+            get(handlers::resolver::list_resolvers).post(handlers::resolver::create_resolver),
+        )
+        .route(
+            "/oracle/resolvers/{id}",
+            delete(handlers::resolver::disable_resolver),
+        )
+        .route(
+            "/oracle/resolvers/{id}/confirm",
+            post(handlers::resolver::confirm_resolver),
+        );
+    let viewer_routes = Router::new()
+        .route("/admin/audit-log", get(handlers::audit::list_audit_log))
+        .route("/admin/jobs", get(handlers::tx_job::list_jobs))
+        .route("/admin/jobs/{id}", get(handlers::tx_job::get_job))
+        .route("/admin/fees/revenue", get(handlers::fees::list_revenue));
+    let super_admin_routes = Router::new()
+        .route(
+            "/admin/accounts",
+            get(handlers::account::list_accounts).post(handlers::account::upsert_account),
+        )
+        .route(
+            "/admin/accounts/{address}",
+            delete(handlers::account::delete_account),
+        )
+        .route("/admin/fees/withdraw", post(handlers::fees::withdraw_fees));
+
+    // Endpoints acting on behalf of the signed-in wallet
+    let wallet_routes = Router::new()
         .route(
             "/favorites",
-            axum::routing::post(handlers::favorite::add_favorite)
-                .delete(handlers::favorite::remove_favorite),
+            post(handlers::favorite::add_favorite).delete(handlers::favorite::remove_favorite),
         )
+        .route("/sponsor", post(handlers::sponsor::sponsor_transaction))
+        .route("/sponsor/quota", get(handlers::sponsor::get_quota))
+        .route_layer(middleware::from_fn_with_state(
+            wallet_limiter,
+            rate_limit::enforce,
+        ))
+        .route_layer(middleware::from_fn_with_state(
+            db.clone(),
+            auth::require_session,
+        ));
+
+    // Sign-In-with-Sui session exchange
+    let auth_routes = Router::new()
+        .route("/auth/challenge", post(handlers::auth::challenge))
+        .route("/auth/login", post(handlers::auth::login))
+        .route_layer(middleware::from_fn_with_state(
+            auth_limiter,
+            rate_limit::enforce,
+        ));
+
+    // App state
+    #[allow(unused_mut)]
+    let mut app = Router::new()
+        .route("/contracts", get(handlers::contract::list_contracts))
+        .route("/contracts/{id}", get(handlers::contract::get_contract))
         .route(
-            "/favorites/{wallet}",
-            get(handlers::favorite::get_favorites),
+            "/contracts/{id}/history",
+            get(handlers::market_history::get_contract_history),
         )
+        .route("/categories", get(handlers::category::list_categories))
         .route(
-            "/market/cancel",
-            axum::routing::post(handlers::cancel::cancel_market),
+            "/favorites/{wallet}",
+            get(handlers::favorite::get_favorites),
         )
+        // Live market events (SSE)
+        .route("/stream", get(handlers::stream::stream_events))
         // Serve dynamic config.js based on backend env vars
-        .route("/config.js", get(handlers::config::get_config));
+        .route("/config.js", get(handlers::config::get_config))
+        // API documentation
+        .route("/openapi.json", get(openapi::openapi_json))
+        .merge(Scalar::with_url("/docs", openapi::ApiDoc::openapi()))
+        .merge(auth_routes)
+        .merge(wallet_routes)
+        .merge(with_role(
+            creator_routes,
+            &db,
+            Role::MarketCreator,
+            &admin_limiter,
+        ))
+        .merge(with_role(oracle_routes, &db, Role::Oracle, &admin_limiter))
+        .merge(with_role(viewer_routes, &db, Role::Viewer, &admin_limiter))
+        .merge(with_role(
+            super_admin_routes,
+            &db,
+            Role::SuperAdmin,
+            &admin_limiter,
+        ));
 
     // Only serve static files in release mode
     #[cfg(not(debug_assertions))]
@@ -89,30 +226,44 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
 
     let app = app
-        .layer(
-            CorsLayer::new()
-                .allow_origin(tower_http::cors::Any)
-                .allow_methods([
-                    axum::http::Method::GET,
-                    axum::http::Method::POST,
-                    axum::http::Method::DELETE,
-                    axum::http::Method::OPTIONS,
-                ])
-                .allow_headers([axum::http::header::CONTENT_TYPE]),
-        )
+        .layer(cors::cors_layer())
         .with_state(db)
-        .layer(axum::Extension(tx));
+        .layer(axum::Extension(tx))
+        .layer(axum::Extension(events));
 
     let addr = SocketAddr::from(([0, 0, 0, 0], 3000));
     println!("listening on {}", addr);
 
-    axum::serve(tokio::net::TcpListener::bind(addr).await?, app)
-        .with_graceful_shutdown(shutdown_signal())
-        .await?;
+    // Connect info provides the peer address for per-IP rate limiting
+    axum::serve(
+        tokio::net::TcpListener::bind(addr).await?,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(shutdown_signal())
+    .await?;
 
     Ok(())
 }
 
+/// Require `role` on an admin route group and apply the shared admin rate limit.
+/// route_layer wraps outward, so auth runs first and the limiter sees the session.
+fn with_role(
+    routes: Router<DatabaseConnection>,
+    db: &DatabaseConnection,
+    role: Role,
+    limiter: &rate_limit::RateLimiter,
+) -> Router<DatabaseConnection> {
+    routes
+        .route_layer(middleware::from_fn_with_state(
+            limiter.clone(),
+            rate_limit::enforce,
+        ))
+        .route_layer(middleware::from_fn_with_state(
+            (db.clone(), role),
+            auth::require_role,
+        ))
+}
+
 async fn shutdown_signal() {
     let ctrl_c = async {
         tokio::signal::ctrl_c()
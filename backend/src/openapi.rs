@@ -0,0 +1,141 @@
+//! OpenAPI document generated from the handler annotations.
+//! Served as JSON at `/openapi.json` and rendered interactively at `/docs`.
+
+use crate::entities::{
+    account, audit_log, category, contract, favorite, fee_withdrawal, market_history,
+    market_resolution, market_resolver, pending_resolution, tx_job,
+};
+use crate::handlers;
+use axum::Json;
+use utoipa::{
+    openapi::security::{Http, HttpAuthScheme, SecurityScheme},
+    Modify, OpenApi,
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Play Sui Backend",
+        description = "Indexer and admin API for the Sui prediction market"
+    ),
+    paths(
+        handlers::contract::list_contracts,
+        handlers::contract::get_contract,
+        handlers::contract::create_contract,
+        handlers::contract::delete_contract,
+        handlers::contract::archive_contract,
+        handlers::contract::restore_contract,
+        handlers::market_history::get_contract_history,
+        handlers::category::list_categories,
+        handlers::oracle::resolve_market,
+        handlers::cancel::cancel_market,
+        handlers::resolution::prepare_resolution,
+        handlers::resolution::list_resolutions,
+        handlers::resolution::submit_resolution,
+        handlers::resolution::abandon_resolution,
+        handlers::resolver::list_resolvers,
+        handlers::resolver::create_resolver,
+        handlers::resolver::confirm_resolver,
+        handlers::resolver::disable_resolver,
+        handlers::favorite::add_favorite,
+        handlers::favorite::remove_favorite,
+        handlers::favorite::get_favorites,
+        handlers::sponsor::sponsor_transaction,
+        handlers::sponsor::get_quota,
+        handlers::config::get_config,
+        handlers::stream::stream_events,
+        handlers::auth::challenge,
+        handlers::auth::login,
+        handlers::audit::list_audit_log,
+        handlers::tx_job::list_jobs,
+        handlers::tx_job::get_job,
+        handlers::fees::withdraw_fees,
+        handlers::fees::list_revenue,
+        handlers::account::list_accounts,
+        handlers::account::upsert_account,
+        handlers::account::delete_account,
+    ),
+    components(schemas(
+        contract::Model,
+        category::Model,
+        favorite::Model,
+        market_history::Model,
+        market_resolution::Model,
+        audit_log::Model,
+        account::Model,
+        account::Role,
+        tx_job::Model,
+        tx_job::JobStatus,
+        fee_withdrawal::Model,
+        pending_resolution::Model,
+        pending_resolution::ResolutionStatus,
+        market_resolver::Model,
+        market_resolver::ResolverStatus,
+        handlers::contract::CreateContract,
+        handlers::contract::MarketDetail,
+        handlers::oracle::ResolveMarketRequest,
+        handlers::oracle::ResolveMarketResponse,
+        handlers::cancel::CancelMarketRequest,
+        handlers::cancel::CancelMarketResponse,
+        handlers::resolution::PrepareResolutionRequest,
+        handlers::resolution::SubmitResolutionRequest,
+        handlers::resolver::CreateResolverRequest,
+        handlers::resolver::ConfirmResolverRequest,
+        crate::resolver::ResolverConfig,
+        crate::resolver::HttpThreshold,
+        crate::resolver::ScheduledManual,
+        crate::resolver::Comparison,
+        handlers::favorite::AddFavorite,
+        handlers::sponsor::SponsorRequest,
+        handlers::sponsor::SponsorResponse,
+        crate::sponsor::SponsorQuota,
+        handlers::stream::StreamEvent,
+        handlers::auth::ChallengeRequest,
+        handlers::auth::ChallengeResponse,
+        handlers::auth::LoginRequest,
+        handlers::auth::LoginResponse,
+        handlers::audit::AuditLogPage,
+        handlers::tx_job::TxJobPage,
+        handlers::fees::WithdrawFeesRequest,
+        handlers::fees::WithdrawFeesResponse,
+        handlers::fees::MarketRevenue,
+        crate::fees::Withdrawable,
+        handlers::account::UpsertAccount,
+        crate::validation::ValidationErrors,
+        crate::validation::FieldError,
+        crate::tx::DryRunReport,
+        crate::tx::GasCost,
+        crate::tx::TxErrorBody,
+    )),
+    tags(
+        (name = "contracts", description = "Market registry and price history"),
+        (name = "categories", description = "Market categories"),
+        (name = "oracle", description = "Resolution and cancellation (admin)"),
+        (name = "favorites", description = "Per-wallet favorite markets"),
+        (name = "sponsor", description = "Gas sponsorship for user bets"),
+        (name = "config", description = "Frontend runtime configuration"),
+        (name = "stream", description = "Live market events over SSE"),
+        (name = "auth", description = "Sign-In-with-Sui session tokens"),
+        (name = "admin", description = "Administrative queries"),
+    ),
+    modifiers(&BearerAuth)
+)]
+pub struct ApiDoc;
+
+/// Registers the `bearer` scheme referenced by admin endpoints
+struct BearerAuth;
+
+impl Modify for BearerAuth {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        if let Some(components) = openapi.components.as_mut() {
+            components.add_security_scheme(
+                "bearer",
+                SecurityScheme::Http(Http::new(HttpAuthScheme::Bearer)),
+            );
+        }
+    }
+}
+
+pub async fn openapi_json() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
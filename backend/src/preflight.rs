@@ -0,0 +1,136 @@
+//! Pre-flight checks for oracle transactions.
+//!
+//! Resolution and cancellation are checked against the database and the
+//! market's current on-chain state before any job is recorded, so requests the
+//! contract would reject fail fast with a precise reason and spend no gas.
+
+use crate::chain::{self, ChainMarket, MarketAbort, MarketLookupError};
+use crate::entities::contract;
+use crate::tx::{Executor, TxError};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use std::str::FromStr;
+use sui_sdk::types::base_types::ObjectID;
+
+fn reject(abort: MarketAbort, reason: String) -> TxError {
+    TxError::Rejected { abort, reason }
+}
+
+/// On-chain state of `market_id`, after ruling out markets the database
+/// already knows are settled
+async fn open_market(
+    db: &DatabaseConnection,
+    executor: &Executor,
+    market_id: &str,
+) -> Result<ChainMarket, TxError> {
+    let known = contract::Entity::find()
+        .filter(contract::Column::Address.eq(market_id))
+        .one(db)
+        .await
+        .map_err(|e| TxError::Config(format!("Failed to fetch contract: {}", e)))?;
+    if let Some(known) = known {
+        if known.cancelled {
+            return Err(reject(
+                MarketAbort::AlreadyResolved,
+                format!("Market {} is cancelled", market_id),
+            ));
+        }
+        if known.resolved {
+            return Err(reject(
+                MarketAbort::AlreadyResolved,
+                format!(
+                    "Market {} is already resolved (winner {})",
+                    market_id,
+                    known
+                        .winner
+                        .map_or("unknown".to_string(), |w| w.to_string())
+                ),
+            ));
+        }
+    }
+
+    let package_id = chain::package_id().map_err(TxError::Config)?;
+    let id = ObjectID::from_str(market_id)
+        .map_err(|e| TxError::Build(format!("Invalid Market ID: {}", e)))?;
+    let market = chain::fetch_market(executor.client(), package_id, id)
+        .await
+        .map_err(|e| match e {
+            MarketLookupError::Rpc(e) => TxError::Submit(e),
+            MarketLookupError::Invalid(e) => TxError::Build(e),
+        })?;
+
+    // The indexer may not have caught up yet
+    if market.cancelled {
+        return Err(reject(
+            MarketAbort::AlreadyResolved,
+            format!("Market {} was cancelled on-chain", market_id),
+        ));
+    }
+    if market.resolved {
+        return Err(reject(
+            MarketAbort::AlreadyResolved,
+            format!(
+                "Market {} was resolved on-chain (winner {})",
+                market_id,
+                market
+                    .winner
+                    .map_or("unknown".to_string(), |w| w.to_string())
+            ),
+        ));
+    }
+    Ok(market)
+}
+
+/// Check that the backend signer can resolve `market_id` with `winner`
+pub async fn check_resolve(
+    db: &DatabaseConnection,
+    executor: &Executor,
+    market_id: &str,
+    winner: u8,
+) -> Result<(), TxError> {
+    let market = open_market(db, executor, market_id).await?;
+
+    if market.oracle != executor.sender() {
+        return Err(reject(
+            MarketAbort::NotAuthorized,
+            format!(
+                "Market oracle is {}, not the backend signer {}; prepare an offline resolution instead",
+                market.oracle,
+                executor.sender()
+            ),
+        ));
+    }
+    if winner >= market.options_count {
+        return Err(reject(
+            MarketAbort::InvalidOutcome,
+            format!(
+                "Winner {} is out of range; the market has {} options",
+                winner, market.options_count
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Check that the backend signer can cancel `market_id`: as its oracle, or
+/// by anyone once it has expired
+pub async fn check_cancel(
+    db: &DatabaseConnection,
+    executor: &Executor,
+    market_id: &str,
+) -> Result<(), TxError> {
+    let market = open_market(db, executor, market_id).await?;
+
+    let now_ms = chrono::Utc::now().timestamp_millis().max(0) as u64;
+    let expired = market.end_time_ms.is_some_and(|end| now_ms > end);
+    if market.oracle != executor.sender() && !expired {
+        return Err(reject(
+            MarketAbort::NotAuthorized,
+            format!(
+                "Market oracle is {}, not the backend signer {}, and the market has not expired",
+                market.oracle,
+                executor.sender()
+            ),
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,180 @@
+//! Fixed-window rate limiting per route group.
+//! Each group has its own budget (`RATE_LIMIT_<GROUP>=<requests>/<seconds>`),
+//! counted separately per client IP and per authenticated wallet.
+
+use crate::auth::AuthSession;
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::{header, HeaderMap, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+// Drop expired windows once the table grows past this many keys
+const PRUNE_THRESHOLD: usize = 10_000;
+
+struct Window {
+    started: Instant,
+    count: u32,
+}
+
+#[derive(Clone)]
+pub struct RateLimiter {
+    group: &'static str,
+    limit: u32,
+    period: Duration,
+    windows: Arc<Mutex<HashMap<String, Window>>>,
+}
+
+impl RateLimiter {
+    /// Build a limiter for `group`, reading `RATE_LIMIT_<GROUP>` or falling back to the default
+    pub fn from_env(group: &'static str, default_limit: u32, default_period_secs: u64) -> Self {
+        let var = format!("RATE_LIMIT_{}", group.to_uppercase());
+        let (limit, period_secs) = std::env::var(&var)
+            .ok()
+            .and_then(|v| {
+                let (n, secs) = v.split_once('/')?;
+                Some((n.trim().parse().ok()?, secs.trim().parse().ok()?))
+            })
+            .unwrap_or((default_limit, default_period_secs));
+
+        println!(
+            "RateLimit: {} = {} requests / {}s",
+            group, limit, period_secs
+        );
+
+        RateLimiter {
+            group,
+            limit,
+            period: Duration::from_secs(period_secs),
+            windows: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Count a hit for `key`; returns how long to wait when over budget
+    fn check(&self, key: &str) -> Result<(), Duration> {
+        let now = Instant::now();
+        let mut windows = self.windows.lock().unwrap();
+
+        if windows.len() > PRUNE_THRESHOLD {
+            windows.retain(|_, w| now.duration_since(w.started) < self.period);
+        }
+
+        let window = windows.entry(key.to_string()).or_insert(Window {
+            started: now,
+            count: 0,
+        });
+
+        if now.duration_since(window.started) >= self.period {
+            window.started = now;
+            window.count = 0;
+        }
+
+        if window.count >= self.limit {
+            return Err(self.period - now.duration_since(window.started));
+        }
+
+        window.count += 1;
+        Ok(())
+    }
+}
+
+/// Client IP, honouring `X-Forwarded-For` only when `RATE_LIMIT_TRUST_PROXY=true`
+fn client_ip(headers: &HeaderMap, peer: SocketAddr) -> IpAddr {
+    let trust_proxy = std::env::var("RATE_LIMIT_TRUST_PROXY")
+        .map(|v| v == "true")
+        .unwrap_or(false);
+
+    if trust_proxy {
+        if let Some(ip) = headers
+            .get("x-forwarded-for")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.split(',').next())
+            .and_then(|v| v.trim().parse().ok())
+        {
+            return ip;
+        }
+    }
+
+    peer.ip()
+}
+
+/// Middleware; layer it inside the auth middleware so the wallet budget applies too
+pub async fn enforce(
+    State(limiter): State<RateLimiter>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let mut keys = vec![format!("ip:{}", client_ip(req.headers(), peer))];
+    if let Some(session) = req.extensions().get::<AuthSession>() {
+        keys.push(format!("wallet:{}", session.address));
+    }
+
+    for key in keys {
+        if let Err(retry_after) = limiter.check(&key) {
+            // Round up so clients never retry a moment too early
+            let secs = retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0);
+            return (
+                StatusCode::TOO_MANY_REQUESTS,
+                [(header::RETRY_AFTER, secs.to_string())],
+                format!("Rate limit exceeded for {} ({})", limiter.group, key),
+            )
+                .into_response();
+        }
+    }
+
+    next.run(req).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter(limit: u32, period: Duration) -> RateLimiter {
+        RateLimiter {
+            group: "test",
+            limit,
+            period,
+            windows: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    #[test]
+    fn allows_up_to_limit_then_reports_wait() {
+        let limiter = limiter(2, Duration::from_secs(60));
+        assert!(limiter.check("ip:1").is_ok());
+        assert!(limiter.check("ip:1").is_ok());
+
+        let wait = limiter.check("ip:1").unwrap_err();
+        assert!(wait > Duration::ZERO && wait <= Duration::from_secs(60));
+    }
+
+    #[test]
+    fn counts_keys_separately() {
+        let limiter = limiter(1, Duration::from_secs(60));
+        assert!(limiter.check("ip:1").is_ok());
+        assert!(limiter.check("ip:1").is_err());
+        assert!(limiter.check("wallet:0x1").is_ok());
+    }
+
+    #[test]
+    fn resets_after_period() {
+        let limiter = limiter(1, Duration::from_millis(20));
+        assert!(limiter.check("ip:1").is_ok());
+        assert!(limiter.check("ip:1").is_err());
+
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(limiter.check("ip:1").is_ok());
+    }
+
+    #[test]
+    fn zero_limit_rejects_everything() {
+        let limiter = limiter(0, Duration::from_secs(60));
+        assert!(limiter.check("ip:1").is_err());
+    }
+}
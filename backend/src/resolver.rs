@@ -0,0 +1,488 @@
+//! Automated market resolvers.
+//!
+//! Each market may have one active resolver, configured by an oracle operator.
+//! Once its `evaluate_at` time has passed, the resolver cron asks it for the
+//! winning outcome and, when it has one, resolves the market through the same
+//! pre-flight checks and transaction job as `POST /oracle/resolve`.
+
+use crate::entities::market_resolver::{self, ResolverStatus};
+use crate::handlers::oracle::ResolutionSource;
+use sea_orm::{ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter};
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+use std::time::Duration;
+use utoipa::ToSchema;
+
+const HTTP_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub struct ResolverCronConfig {
+    pub interval: Duration,
+}
+
+impl ResolverCronConfig {
+    pub fn from_env() -> Self {
+        let secs = std::env::var("RESOLVER_INTERVAL_SECS")
+            .ok()
+            .and_then(|v| v.trim().parse().ok())
+            .unwrap_or(60);
+        ResolverCronConfig {
+            interval: Duration::from_secs(secs),
+        }
+    }
+}
+
+/// Hosts an `http_threshold` resolver may fetch even though they are not
+/// public (`RESOLVER_ALLOWED_HOSTS`, comma-separated), e.g. an internal price feed
+pub fn allowed_hosts() -> Vec<String> {
+    std::env::var("RESOLVER_ALLOWED_HOSTS")
+        .unwrap_or_default()
+        .split(',')
+        .map(|h| h.trim().to_ascii_lowercase())
+        .filter(|h| !h.is_empty())
+        .collect()
+}
+
+/// Whether `ip` is reachable on the public internet; loopback, private,
+/// link-local (cloud metadata) and similar ranges are not
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            let [a, b, ..] = v4.octets();
+            !(v4.is_private()
+                || v4.is_loopback()
+                || v4.is_link_local()
+                || v4.is_unspecified()
+                || v4.is_broadcast()
+                || v4.is_documentation()
+                || v4.is_multicast()
+                || a == 0
+                || (a == 100 && (b & 0xc0) == 64)) // Shared address space (CGNAT)
+        }
+        IpAddr::V6(v6) => match v6.to_ipv4_mapped() {
+            Some(v4) => is_public(IpAddr::V4(v4)),
+            None => {
+                let first = v6.segments()[0];
+                !(v6.is_loopback()
+                    || v6.is_unspecified()
+                    || v6.is_multicast()
+                    || (first & 0xfe00) == 0xfc00 // Unique local
+                    || (first & 0xffc0) == 0xfe80) // Link-local
+            }
+        },
+    }
+}
+
+/// Refuse URLs whose host is, or resolves to, a non-public address, unless
+/// the host is in `RESOLVER_ALLOWED_HOSTS`. Checked when a resolver is
+/// configured and again before each fetch, as DNS answers can change.
+pub async fn check_public_url(url: &str) -> Result<(), String> {
+    let url = reqwest::Url::parse(url).map_err(|e| format!("invalid URL: {}", e))?;
+    let host = url.host_str().ok_or("URL has no host")?;
+    if allowed_hosts().contains(&host.to_ascii_lowercase()) {
+        return Ok(());
+    }
+
+    // IPv6 literals keep their brackets in the URL
+    let ips: Vec<IpAddr> = match host.trim_matches(&['[', ']'][..]).parse() {
+        Ok(ip) => vec![ip],
+        Err(_) => {
+            let port = url.port_or_known_default().unwrap_or(80);
+            tokio::net::lookup_host((host, port))
+                .await
+                .map_err(|e| format!("cannot resolve {}: {}", host, e))?
+                .map(|addr| addr.ip())
+                .collect()
+        }
+    };
+    match ips.into_iter().find(|ip| !is_public(*ip)) {
+        Some(ip) => Err(format!(
+            "host {} is not public ({}); allow it with RESOLVER_ALLOWED_HOSTS",
+            host, ip
+        )),
+        None => Ok(()),
+    }
+}
+
+/// The outcome a resolver settled on
+pub struct Decision {
+    pub winner: u8,
+    pub source: ResolutionSource,
+}
+
+pub trait Resolver {
+    /// The winning outcome, or `None` when it is not known yet and the
+    /// resolver should be asked again on the next run
+    async fn evaluate(&self) -> Result<Option<Decision>, String>;
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ResolverConfig {
+    HttpThreshold(HttpThreshold),
+    ScheduledManual(ScheduledManual),
+}
+
+impl ResolverConfig {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ResolverConfig::HttpThreshold(_) => "http_threshold",
+            ResolverConfig::ScheduledManual(_) => "scheduled_manual",
+        }
+    }
+
+    /// Outcome indices the configuration can produce
+    pub fn winners(&self) -> Vec<u8> {
+        match self {
+            ResolverConfig::HttpThreshold(http) => vec![http.winner_if_true, http.winner_if_false],
+            ResolverConfig::ScheduledManual(manual) => {
+                manual.confirmed_winner.into_iter().collect()
+            }
+        }
+    }
+
+    pub async fn evaluate(&self) -> Result<Option<Decision>, String> {
+        match self {
+            ResolverConfig::HttpThreshold(http) => {
+                check_public_url(&http.url)
+                    .await
+                    .map_err(|e| format!("Refusing to fetch {}: {}", http.url, e))?;
+                http.evaluate().await
+            }
+            ResolverConfig::ScheduledManual(manual) => manual.evaluate().await,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Comparison {
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+    Eq,
+}
+
+impl Comparison {
+    fn holds(self, value: f64, threshold: f64) -> bool {
+        match self {
+            Comparison::Gt => value > threshold,
+            Comparison::Gte => value >= threshold,
+            Comparison::Lt => value < threshold,
+            Comparison::Lte => value <= threshold,
+            Comparison::Eq => value == threshold,
+        }
+    }
+
+    fn symbol(self) -> &'static str {
+        match self {
+            Comparison::Gt => ">",
+            Comparison::Gte => ">=",
+            Comparison::Lt => "<",
+            Comparison::Lte => "<=",
+            Comparison::Eq => "==",
+        }
+    }
+}
+
+/// Fetch a JSON document and compare one numeric field to a threshold
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct HttpThreshold {
+    pub url: String,
+    /// JSON pointer (RFC 6901) to the field, e.g. `/data/price`
+    pub pointer: String,
+    pub comparison: Comparison,
+    pub threshold: f64,
+    pub winner_if_true: u8,
+    pub winner_if_false: u8,
+}
+
+impl Resolver for HttpThreshold {
+    async fn evaluate(&self) -> Result<Option<Decision>, String> {
+        // A redirect could lead past `check_public_url`
+        let client = reqwest::Client::builder()
+            .timeout(HTTP_TIMEOUT)
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+        let document: serde_json::Value = client
+            .get(&self.url)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| format!("Failed to fetch {}: {}", self.url, e))?
+            .json()
+            .await
+            .map_err(|e| format!("{} did not return JSON: {}", self.url, e))?;
+
+        let field = document
+            .pointer(&self.pointer)
+            .ok_or_else(|| format!("{} has no field {}", self.url, self.pointer))?;
+        // Numbers are often served as strings to keep their precision
+        let value = field
+            .as_f64()
+            .or_else(|| field.as_str().and_then(|s| s.trim().parse().ok()))
+            .ok_or_else(|| format!("{} at {} is not a number", field, self.pointer))?;
+
+        let holds = self.comparison.holds(value, self.threshold);
+        Ok(Some(Decision {
+            winner: if holds {
+                self.winner_if_true
+            } else {
+                self.winner_if_false
+            },
+            source: ResolutionSource {
+                source_url: Some(self.url.clone()),
+                notes: Some(format!(
+                    "{} = {} ({} {} is {})",
+                    self.pointer,
+                    value,
+                    self.comparison.symbol(),
+                    self.threshold,
+                    holds
+                )),
+            },
+        }))
+    }
+}
+
+/// Resolve on schedule with the outcome an operator confirmed beforehand
+#[derive(Clone, Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct ScheduledManual {
+    /// Set through `POST /oracle/resolvers/{id}/confirm`; nothing happens until then
+    pub confirmed_winner: Option<u8>,
+    pub confirmed_by: Option<String>,
+    pub source_url: Option<String>,
+    pub notes: Option<String>,
+}
+
+impl Resolver for ScheduledManual {
+    async fn evaluate(&self) -> Result<Option<Decision>, String> {
+        Ok(self.confirmed_winner.map(|winner| Decision {
+            winner,
+            source: ResolutionSource {
+                source_url: self.source_url.clone(),
+                notes: self.notes.clone(),
+            },
+        }))
+    }
+}
+
+/// The active resolver of a market, if any
+pub async fn active_for(
+    db: &DatabaseConnection,
+    market_address: &str,
+) -> Result<Option<market_resolver::Model>, DbErr> {
+    market_resolver::Entity::find()
+        .filter(market_resolver::Column::MarketAddress.eq(market_address))
+        .filter(market_resolver::Column::Status.eq(ResolverStatus::Active))
+        .one(db)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{http::StatusCode, routing::get, Json, Router};
+    use serde_json::json;
+
+    /// Serve fixed JSON documents on a local port; returns the base URL
+    async fn mock_server() -> String {
+        let app = Router::new()
+            .route(
+                "/number",
+                get(|| async { Json(json!({ "data": { "price": 105.5 } })) }),
+            )
+            .route(
+                "/string",
+                get(|| async { Json(json!({ "data": { "price": " 99.25 " } })) }),
+            )
+            .route(
+                "/text",
+                get(|| async { Json(json!({ "data": { "price": "n/a" } })) }),
+            )
+            .route(
+                "/error",
+                get(|| async { (StatusCode::SERVICE_UNAVAILABLE, "down") }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{}", addr)
+    }
+
+    fn http(url: String, pointer: &str, comparison: Comparison, threshold: f64) -> HttpThreshold {
+        HttpThreshold {
+            url,
+            pointer: pointer.to_string(),
+            comparison,
+            threshold,
+            winner_if_true: 0,
+            winner_if_false: 1,
+        }
+    }
+
+    #[test]
+    fn comparisons() {
+        let cases = [
+            (Comparison::Gt, 2.0, 1.0, true),
+            (Comparison::Gt, 1.0, 1.0, false),
+            (Comparison::Gte, 1.0, 1.0, true),
+            (Comparison::Gte, 0.5, 1.0, false),
+            (Comparison::Lt, 0.5, 1.0, true),
+            (Comparison::Lt, 1.0, 1.0, false),
+            (Comparison::Lte, 1.0, 1.0, true),
+            (Comparison::Lte, 2.0, 1.0, false),
+            (Comparison::Eq, 1.0, 1.0, true),
+            (Comparison::Eq, 1.5, 1.0, false),
+        ];
+        for (comparison, value, threshold, expected) in cases {
+            assert_eq!(
+                comparison.holds(value, threshold),
+                expected,
+                "{} {} {}",
+                value,
+                comparison.symbol(),
+                threshold
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn number_field_picks_winner_by_threshold() {
+        let base = mock_server().await;
+        let url = format!("{}/number", base);
+
+        let above = http(url.clone(), "/data/price", Comparison::Gt, 100.0)
+            .evaluate()
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(above.winner, 0);
+        assert_eq!(above.source.source_url.as_deref(), Some(url.as_str()));
+        assert!(above.source.notes.unwrap().contains("105.5"));
+
+        let below = http(url, "/data/price", Comparison::Lt, 100.0)
+            .evaluate()
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(below.winner, 1);
+    }
+
+    #[tokio::test]
+    async fn numeric_string_is_parsed() {
+        let base = mock_server().await;
+        let decision = http(
+            format!("{}/string", base),
+            "/data/price",
+            Comparison::Eq,
+            99.25,
+        )
+        .evaluate()
+        .await
+        .unwrap()
+        .unwrap();
+        assert_eq!(decision.winner, 0);
+    }
+
+    #[tokio::test]
+    async fn missing_pointer_is_an_error() {
+        let base = mock_server().await;
+        let error = http(
+            format!("{}/number", base),
+            "/data/volume",
+            Comparison::Gt,
+            0.0,
+        )
+        .evaluate()
+        .await
+        .err()
+        .unwrap();
+        assert!(error.contains("has no field /data/volume"), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn non_numeric_field_is_an_error() {
+        let base = mock_server().await;
+        let error = http(format!("{}/text", base), "/data/price", Comparison::Gt, 0.0)
+            .evaluate()
+            .await
+            .err()
+            .unwrap();
+        assert!(error.contains("is not a number"), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn non_2xx_response_is_an_error() {
+        let base = mock_server().await;
+        let error = http(
+            format!("{}/error", base),
+            "/data/price",
+            Comparison::Gt,
+            0.0,
+        )
+        .evaluate()
+        .await
+        .err()
+        .unwrap();
+        assert!(error.starts_with("Failed to fetch"), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn non_public_hosts_are_refused() {
+        for url in [
+            "http://127.0.0.1:8080/price",
+            "http://169.254.169.254/latest/meta-data",
+            "http://10.1.2.3/",
+            "http://192.168.0.1/",
+            "http://100.64.0.1/",
+            "http://0.0.0.0/",
+            "http://[::1]/",
+            "http://[fd00::1]/",
+            "http://[::ffff:127.0.0.1]/",
+        ] {
+            let error = check_public_url(url).await.err();
+            assert!(
+                error.is_some_and(|e| e.contains("is not public")),
+                "{}",
+                url
+            );
+        }
+        for url in ["https://8.8.8.8/price", "https://[2606:4700::1111]/"] {
+            assert_eq!(check_public_url(url).await, Ok(()), "{}", url);
+        }
+    }
+
+    #[tokio::test]
+    async fn scheduled_manual_waits_for_confirmation() {
+        let mut manual = ScheduledManual::default();
+        assert!(manual.evaluate().await.unwrap().is_none());
+
+        manual.confirmed_winner = Some(2);
+        manual.notes = Some("Official result".to_string());
+        let decision = manual.evaluate().await.unwrap().unwrap();
+        assert_eq!(decision.winner, 2);
+        assert_eq!(decision.source.notes.as_deref(), Some("Official result"));
+    }
+
+    #[test]
+    fn config_round_trips_with_kind_tag() {
+        let config: ResolverConfig = serde_json::from_value(json!({
+            "kind": "http_threshold",
+            "url": "http://127.0.0.1:1/price",
+            "pointer": "/price",
+            "comparison": "gte",
+            "threshold": 10,
+            "winner_if_true": 0,
+            "winner_if_false": 1,
+        }))
+        .unwrap();
+        assert_eq!(config.kind(), "http_threshold");
+        assert_eq!(config.winners(), vec![0, 1]);
+        assert_eq!(
+            serde_json::to_value(&config).unwrap()["kind"],
+            "http_threshold"
+        );
+    }
+}
@@ -0,0 +1,116 @@
+//! Backend transaction signer.
+//!
+//! Configured from the environment, first match wins:
+//! - `SUI_SIGNER_PRIVATE_KEY`: a bech32 `suiprivkey1...` secret
+//! - `SUI_SIGNER_PRIVATE_KEY_FILE`: path to a file holding that secret
+//! - `SUI_KEYSTORE_PATH` (default `~/.sui/sui_config/sui.keystore`) together with
+//!   `SUI_SIGNER_ADDRESS`, an address or alias in that keystore (default: first entry)
+
+use shared_crypto::intent::{Intent, IntentMessage};
+use std::path::PathBuf;
+use std::str::FromStr;
+use sui_keys::keystore::{AccountKeystore, FileBasedKeystore};
+use sui_sdk::types::{
+    base_types::SuiAddress,
+    crypto::{Signature, SuiKeyPair},
+    transaction::TransactionData,
+};
+
+pub enum Signer {
+    Keystore {
+        keystore: FileBasedKeystore,
+        address: SuiAddress,
+    },
+    Key {
+        keypair: SuiKeyPair,
+        address: SuiAddress,
+    },
+}
+
+impl Signer {
+    pub fn from_env() -> Result<Signer, String> {
+        if let Some(secret) = private_key_from_env()? {
+            let keypair = SuiKeyPair::decode(secret.trim())
+                .map_err(|e| format!("Invalid signer private key: {}", e))?;
+            let address = SuiAddress::from(&keypair.public());
+            return Ok(Signer::Key { keypair, address });
+        }
+
+        let path = keystore_path();
+        let keystore = FileBasedKeystore::load_or_create(&path)
+            .map_err(|e| format!("Failed to load keystore {}: {}", path.display(), e))?;
+
+        let address = match std::env::var("SUI_SIGNER_ADDRESS") {
+            Ok(wanted) if !wanted.trim().is_empty() => {
+                resolve_keystore_address(&keystore, wanted.trim())?
+            }
+            _ => *keystore
+                .addresses()
+                .first()
+                .ok_or_else(|| format!("No accounts found in {}", path.display()))?,
+        };
+
+        Ok(Signer::Keystore { keystore, address })
+    }
+
+    pub fn address(&self) -> SuiAddress {
+        match self {
+            Signer::Keystore { address, .. } | Signer::Key { address, .. } => *address,
+        }
+    }
+
+    pub async fn sign(&self, tx_data: &TransactionData) -> Result<Signature, String> {
+        match self {
+            Signer::Keystore { keystore, address } => keystore
+                .sign_secure(address, tx_data, Intent::sui_transaction())
+                .await
+                .map_err(|e| format!("Failed to sign transaction: {}", e)),
+            Signer::Key { keypair, .. } => Ok(Signature::new_secure(
+                &IntentMessage::new(Intent::sui_transaction(), tx_data.clone()),
+                keypair,
+            )),
+        }
+    }
+}
+
+fn private_key_from_env() -> Result<Option<String>, String> {
+    if let Ok(secret) = std::env::var("SUI_SIGNER_PRIVATE_KEY") {
+        if !secret.trim().is_empty() {
+            return Ok(Some(secret));
+        }
+    }
+    match std::env::var("SUI_SIGNER_PRIVATE_KEY_FILE") {
+        Ok(path) if !path.trim().is_empty() => std::fs::read_to_string(path.trim())
+            .map(Some)
+            .map_err(|e| format!("Failed to read SUI_SIGNER_PRIVATE_KEY_FILE: {}", e)),
+        _ => Ok(None),
+    }
+}
+
+fn keystore_path() -> PathBuf {
+    match std::env::var("SUI_KEYSTORE_PATH") {
+        Ok(path) if !path.trim().is_empty() => PathBuf::from(path.trim()),
+        _ => {
+            let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+            PathBuf::from(home).join(".sui/sui_config/sui.keystore")
+        }
+    }
+}
+
+/// `wanted` is either a keystore address or one of its aliases
+fn resolve_keystore_address(
+    keystore: &FileBasedKeystore,
+    wanted: &str,
+) -> Result<SuiAddress, String> {
+    if let Ok(address) = SuiAddress::from_str(wanted) {
+        return if keystore.addresses().contains(&address) {
+            Ok(address)
+        } else {
+            Err(format!("Signer address {} is not in the keystore", address))
+        };
+    }
+    keystore
+        .get_address_by_alias(wanted.to_string())
+        .copied()
+        .map_err(|_| format!("No keystore entry with alias {:?}", wanted))
+}
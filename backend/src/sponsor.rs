@@ -0,0 +1,311 @@
+//! Gas sponsorship for user bets.
+//!
+//! A wallet without SUI for gas builds its `place_bet` / `claim_reward`
+//! transaction kind and sends it to the backend, which attaches a coin from the
+//! signer's gas pool and signs as gas owner. The wallet adds its own signature
+//! and submits both. Each wallet may be sponsored a limited number of
+//! transactions and gas budget per rolling day.
+
+use crate::entities::sponsored_tx;
+use crate::tx::MARKET_MODULE;
+use axum::http::StatusCode;
+use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
+use serde::Serialize;
+use std::sync::LazyLock;
+use std::time::Duration;
+use sui_sdk::types::{
+    base_types::{ObjectID, SuiAddress},
+    transaction::{Argument, Command, TransactionKind},
+};
+use tokio::sync::Mutex;
+use utoipa::ToSchema;
+
+/// `market` functions a sponsored transaction may call
+pub const SPONSORED_FUNCTIONS: &[&str] = &["place_bet", "claim_reward"];
+
+// Serializes quota checks so concurrent requests cannot overdraw a wallet
+static QUOTA_LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
+
+pub struct SponsorConfig {
+    pub daily_tx_limit: u64, // 0 disables sponsorship
+    pub daily_gas_limit: u64,
+    pub max_gas_budget: u64, // Per transaction
+    pub coin_hold: Duration, // How long the sponsor coin stays reserved for the wallet
+}
+
+impl SponsorConfig {
+    pub fn from_env() -> Self {
+        fn env_u64(key: &str, default: u64) -> u64 {
+            std::env::var(key)
+                .ok()
+                .and_then(|v| v.trim().parse().ok())
+                .unwrap_or(default)
+        }
+        SponsorConfig {
+            daily_tx_limit: env_u64("SPONSOR_DAILY_TX_LIMIT", 20),
+            daily_gas_limit: env_u64("SPONSOR_DAILY_GAS_MIST", 200_000_000),
+            max_gas_budget: env_u64("SPONSOR_GAS_BUDGET_MAX", 20_000_000),
+            coin_hold: Duration::from_secs(env_u64("SPONSOR_COIN_HOLD_SECS", 120)),
+        }
+    }
+}
+
+/// A wallet's sponsorship usage over the last 24 hours
+#[derive(Serialize, ToSchema)]
+pub struct SponsorQuota {
+    pub tx_limit: u64,
+    pub tx_used: u64,
+    /// Gas budgets in MIST; a sponsored transaction counts its full budget
+    pub gas_limit: u64,
+    pub gas_used: u64,
+}
+
+impl SponsorQuota {
+    /// Why a transaction with `gas_budget` would exceed the quota, if it would
+    pub fn exceeded_by(&self, gas_budget: u64) -> Option<String> {
+        if self.tx_used >= self.tx_limit {
+            return Some(format!(
+                "Daily limit of {} sponsored transactions reached",
+                self.tx_limit
+            ));
+        }
+        if self.gas_used.saturating_add(gas_budget) > self.gas_limit {
+            return Some(format!(
+                "Daily sponsored gas limit of {} MIST reached ({} used)",
+                self.gas_limit, self.gas_used
+            ));
+        }
+        None
+    }
+}
+
+/// Check that `kind` only calls sponsored `market` functions of our package and
+/// never touches the gas coin, which belongs to the sponsor.
+/// Returns the functions called.
+pub fn check_kind(kind: &TransactionKind, package_id: ObjectID) -> Result<Vec<String>, String> {
+    let TransactionKind::ProgrammableTransaction(ptb) = kind else {
+        return Err("Only programmable transactions can be sponsored".to_string());
+    };
+
+    let mut functions = Vec::new();
+    for command in &ptb.commands {
+        let arguments: Vec<&Argument> = match command {
+            Command::MoveCall(call) => {
+                if call.package != package_id
+                    || call.module.as_str() != MARKET_MODULE
+                    || !SPONSORED_FUNCTIONS.contains(&call.function.as_str())
+                {
+                    return Err(format!(
+                        "Call to {}::{}::{} is not sponsored",
+                        call.package, call.module, call.function
+                    ));
+                }
+                functions.push(call.function.to_string());
+                call.arguments.iter().collect()
+            }
+            // Needed to pay a bet from one of the wallet's own coins
+            Command::SplitCoins(coin, amounts) => std::iter::once(coin).chain(amounts).collect(),
+            Command::MergeCoins(coin, coins) => std::iter::once(coin).chain(coins).collect(),
+            _ => return Err("Only market calls and coin splits/merges are sponsored".to_string()),
+        };
+        if arguments.iter().any(|a| matches!(a, Argument::GasCoin)) {
+            return Err("Sponsored transactions may not use the gas coin".to_string());
+        }
+    }
+
+    if functions.is_empty() {
+        return Err("Transaction calls no sponsored function".to_string());
+    }
+    Ok(functions)
+}
+
+pub async fn quota(
+    db: &DatabaseConnection,
+    wallet: SuiAddress,
+    config: &SponsorConfig,
+) -> Result<SponsorQuota, (StatusCode, String)> {
+    let cutoff = (chrono::Utc::now() - chrono::Duration::hours(24)).to_rfc3339();
+    let recent = sponsored_tx::Entity::find()
+        .filter(sponsored_tx::Column::Wallet.eq(wallet.to_string()))
+        .filter(sponsored_tx::Column::CreatedAt.gte(cutoff))
+        .all(db)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(SponsorQuota {
+        tx_limit: config.daily_tx_limit,
+        tx_used: recent.len() as u64,
+        gas_limit: config.daily_gas_limit,
+        gas_used: recent.iter().map(|r| r.gas_budget.max(0) as u64).sum(),
+    })
+}
+
+/// Charge the wallet's quota with the per-transaction cap before building;
+/// `settle` lowers it to the real budget. 429 when over quota.
+pub async fn reserve(
+    db: &DatabaseConnection,
+    wallet: SuiAddress,
+    functions: &[String],
+    config: &SponsorConfig,
+) -> Result<sponsored_tx::Model, (StatusCode, String)> {
+    let _guard = QUOTA_LOCK.lock().await;
+    let usage = quota(db, wallet, config).await?;
+    if let Some(reason) = usage.exceeded_by(config.max_gas_budget) {
+        return Err((StatusCode::TOO_MANY_REQUESTS, reason));
+    }
+
+    sponsored_tx::ActiveModel {
+        wallet: Set(wallet.to_string()),
+        functions: Set(functions.join(",")),
+        digest: Set(None),
+        gas_budget: Set(config.max_gas_budget as i64),
+        created_at: Set(chrono::Utc::now().to_rfc3339()),
+        ..Default::default()
+    }
+    .insert(db)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+/// Record the signed transaction, or give the reservation back when signing failed
+pub async fn settle(
+    db: &DatabaseConnection,
+    reservation: sponsored_tx::Model,
+    signed: Option<(String, u64)>,
+) -> Result<(), (StatusCode, String)> {
+    let result = match signed {
+        Some((digest, gas_budget)) => {
+            let mut active: sponsored_tx::ActiveModel = reservation.into();
+            active.digest = Set(Some(digest));
+            active.gas_budget = Set(gas_budget as i64);
+            active.update(db).await.map(|_| ())
+        }
+        None => sponsored_tx::Entity::delete_by_id(reservation.id)
+            .exec(db)
+            .await
+            .map(|_| ()),
+    };
+    result.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_sdk::types::{
+        programmable_transaction_builder::ProgrammableTransactionBuilder, Identifier,
+    };
+
+    fn package() -> ObjectID {
+        ObjectID::from_single_byte(0xaa)
+    }
+
+    fn call(package: ObjectID, function: &str, arguments: Vec<Argument>) -> Command {
+        Command::move_call(
+            package,
+            Identifier::new(MARKET_MODULE).unwrap(),
+            Identifier::new(function).unwrap(),
+            vec![],
+            arguments,
+        )
+    }
+
+    fn kind(commands: Vec<Command>) -> TransactionKind {
+        let mut ptb = ProgrammableTransactionBuilder::new();
+        for command in commands {
+            ptb.command(command);
+        }
+        TransactionKind::ProgrammableTransaction(ptb.finish())
+    }
+
+    #[test]
+    fn accepts_bet_paid_from_own_coin() {
+        let kind = kind(vec![
+            Command::SplitCoins(Argument::Input(0), vec![Argument::Input(1)]),
+            call(
+                package(),
+                "place_bet",
+                vec![Argument::Input(2), Argument::NestedResult(0, 0)],
+            ),
+        ]);
+        assert_eq!(check_kind(&kind, package()).unwrap(), vec!["place_bet"]);
+    }
+
+    #[test]
+    fn accepts_merged_claims() {
+        let kind = kind(vec![
+            call(package(), "claim_reward", vec![Argument::Input(0)]),
+            call(package(), "claim_reward", vec![Argument::Input(1)]),
+            Command::MergeCoins(Argument::Result(0), vec![Argument::Result(1)]),
+        ]);
+        assert_eq!(
+            check_kind(&kind, package()).unwrap(),
+            vec!["claim_reward", "claim_reward"]
+        );
+    }
+
+    #[test]
+    fn rejects_other_packages_and_functions() {
+        let other = kind(vec![call(
+            ObjectID::from_single_byte(0xbb),
+            "place_bet",
+            vec![],
+        )]);
+        assert!(check_kind(&other, package()).is_err());
+
+        let admin = kind(vec![call(package(), "resolve_market", vec![])]);
+        assert!(check_kind(&admin, package()).is_err());
+    }
+
+    #[test]
+    fn rejects_gas_coin_use() {
+        let kind = kind(vec![
+            Command::SplitCoins(Argument::GasCoin, vec![Argument::Input(0)]),
+            call(package(), "place_bet", vec![Argument::NestedResult(0, 0)]),
+        ]);
+        assert!(check_kind(&kind, package()).is_err());
+
+        let direct = kind(vec![call(package(), "place_bet", vec![Argument::GasCoin])]);
+        assert!(check_kind(&direct, package()).is_err());
+    }
+
+    #[test]
+    fn rejects_transfers_and_calls_without_market_function() {
+        let transfer = kind(vec![
+            call(package(), "claim_reward", vec![Argument::Input(0)]),
+            Command::TransferObjects(vec![Argument::Result(0)], Argument::Input(1)),
+        ]);
+        assert!(check_kind(&transfer, package()).is_err());
+
+        let split_only = kind(vec![Command::SplitCoins(
+            Argument::Input(0),
+            vec![Argument::Input(1)],
+        )]);
+        assert!(check_kind(&split_only, package()).is_err());
+    }
+
+    fn quota(tx_used: u64, gas_used: u64) -> SponsorQuota {
+        SponsorQuota {
+            tx_limit: 3,
+            tx_used,
+            gas_limit: 100,
+            gas_used,
+        }
+    }
+
+    #[test]
+    fn quota_allows_within_limits() {
+        assert!(quota(0, 0).exceeded_by(100).is_none());
+        assert!(quota(2, 60).exceeded_by(40).is_none());
+    }
+
+    #[test]
+    fn quota_counts_transactions() {
+        assert!(quota(3, 0).exceeded_by(1).is_some());
+    }
+
+    #[test]
+    fn quota_counts_gas_including_new_budget() {
+        assert!(quota(1, 60).exceeded_by(41).is_some());
+        assert!(quota(1, u64::MAX).exceeded_by(1).is_some());
+    }
+}
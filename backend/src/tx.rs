@@ -0,0 +1,711 @@
+//! Shared build → sign → submit → check pipeline for backend-signed Move calls.
+
+use crate::chain::{self, MarketAbort};
+use crate::gas::{self, GasLease};
+use crate::signer::Signer;
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::str::FromStr;
+use sui_sdk::{
+    json::SuiJsonValue,
+    rpc_types::{
+        DryRunTransactionBlockResponse, MoveCallParams, RPCTransactionRequestParams, SuiEvent,
+        SuiExecutionStatus, SuiTransactionBlockEffectsAPI, SuiTransactionBlockResponse,
+        SuiTransactionBlockResponseOptions,
+    },
+    types::{
+        base_types::{ObjectID, SequenceNumber, SuiAddress},
+        crypto::Signature,
+        digests::TransactionDigest,
+        gas::GasCostSummary,
+        quorum_driver_types::ExecuteTransactionRequestType,
+        transaction::{GasData, Transaction, TransactionData, TransactionDataAPI, TransactionKind},
+    },
+    SuiClient, SuiClientBuilder,
+};
+use utoipa::{IntoParams, ToSchema};
+
+pub const MARKET_MODULE: &str = "market";
+/// Response headers carrying gas figures where the body has no room for them
+pub const GAS_BUDGET_HEADER: &str = "x-gas-budget";
+pub const GAS_USED_HEADER: &str = "x-gas-used";
+
+/// A call to `market::<function>` in the configured package
+pub struct MoveCall {
+    pub function: &'static str,
+    pub args: Vec<SuiJsonValue>,
+}
+
+impl MoveCall {
+    pub fn new(function: &'static str, args: Vec<SuiJsonValue>) -> Self {
+        MoveCall { function, args }
+    }
+}
+
+#[derive(Debug)]
+pub enum TxError {
+    /// Missing or invalid backend configuration (package, signer, network)
+    Config(String),
+    /// The transaction could not be built, e.g. bad arguments or no gas
+    Build(String),
+    /// The fullnode rejected or failed to receive the transaction
+    Submit(String),
+    /// The transaction executed and aborted; gas was still charged
+    Failed { digest: String, error: String },
+    /// A `market` abort, caught by the dry run (no digest, no gas) or on execution
+    Aborted {
+        digest: Option<String>,
+        abort: MarketAbort,
+    },
+    /// Another transaction for the same market has not finished yet
+    InFlight(String),
+    /// A pre-flight check found the contract would abort; nothing was submitted
+    Rejected { abort: MarketAbort, reason: String },
+}
+
+impl TxError {
+    /// Digest of an executed-but-failed transaction, for the audit trail
+    pub fn digest(&self) -> Option<String> {
+        match self {
+            TxError::Failed { digest, .. } => Some(digest.clone()),
+            TxError::Aborted { digest, .. } => digest.clone(),
+            _ => None,
+        }
+    }
+
+    /// Classify an execution error: a `market` abort, or a generic failure of the
+    /// executed (`digest`) or dry-run transaction `label`
+    fn execution(error: String, digest: Option<String>, label: &str) -> TxError {
+        match (MarketAbort::parse(&error), digest) {
+            (Some(abort), digest) => TxError::Aborted { digest, abort },
+            (None, Some(digest)) => TxError::Failed { digest, error },
+            // Nothing was submitted, so no gas is lost
+            (None, None) => TxError::Build(format!("{} would fail: {}", label, error)),
+        }
+    }
+
+    /// Machine-readable error code for API responses
+    pub fn code(&self) -> &'static str {
+        match self {
+            TxError::Config(_) => "backend_misconfigured",
+            TxError::Build(_) => "build_failed",
+            TxError::Submit(_) => "submit_failed",
+            TxError::Failed { .. } => "execution_failed",
+            TxError::Aborted { abort, .. } | TxError::Rejected { abort, .. } => abort.name(),
+            TxError::InFlight(_) => "in_flight",
+        }
+    }
+
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            TxError::Config(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            TxError::Build(_) | TxError::Failed { .. } => StatusCode::UNPROCESSABLE_ENTITY,
+            TxError::Submit(_) => StatusCode::BAD_GATEWAY,
+            TxError::InFlight(_) => StatusCode::CONFLICT,
+            TxError::Aborted { abort, .. } | TxError::Rejected { abort, .. } => abort.status_code(),
+        }
+    }
+}
+
+impl std::fmt::Display for TxError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TxError::Config(e)
+            | TxError::Build(e)
+            | TxError::Submit(e)
+            | TxError::InFlight(e)
+            | TxError::Rejected { reason: e, .. } => write!(f, "{}", e),
+            TxError::Failed { digest, error } => {
+                write!(f, "Transaction {} failed: {}", digest, error)
+            }
+            TxError::Aborted {
+                digest: Some(digest),
+                abort,
+            } => write!(
+                f,
+                "Transaction {} aborted: {} (code {})",
+                digest,
+                abort.message(),
+                abort.code()
+            ),
+            TxError::Aborted {
+                digest: None,
+                abort,
+            } => write!(
+                f,
+                "Transaction would abort: {} (code {})",
+                abort.message(),
+                abort.code()
+            ),
+        }
+    }
+}
+
+impl From<TxError> for (StatusCode, String) {
+    fn from(e: TxError) -> Self {
+        (e.status_code(), e.to_string())
+    }
+}
+
+/// JSON error body of endpoints that submit market transactions
+#[derive(Serialize, ToSchema)]
+pub struct TxErrorBody {
+    /// e.g. `market_already_resolved`, `not_authorized`, `in_flight`, `submit_failed`
+    pub error: &'static str,
+    pub message: String,
+    /// `market` module abort code, when the transaction aborted or would have
+    pub abort_code: Option<u64>,
+    /// Set when the transaction executed (and was charged gas)
+    pub digest: Option<String>,
+}
+
+impl IntoResponse for TxError {
+    fn into_response(self) -> Response {
+        let body = TxErrorBody {
+            error: self.code(),
+            message: self.to_string(),
+            abort_code: match &self {
+                TxError::Aborted { abort, .. } | TxError::Rejected { abort, .. } => {
+                    Some(abort.code())
+                }
+                _ => None,
+            },
+            digest: self.digest(),
+        };
+        (self.status_code(), Json(body)).into_response()
+    }
+}
+
+/// A signed transaction ready to send (or resend)
+pub struct SignedTx {
+    pub transaction: Transaction,
+    pub digest: String,
+    pub gas_budget: u64,
+    pub label: String,
+}
+
+impl SignedTx {
+    /// Gas coins paid by this transaction
+    pub fn gas_coins(&self) -> Vec<ObjectID> {
+        self.transaction
+            .data()
+            .transaction_data()
+            .gas()
+            .iter()
+            .map(|r| r.0)
+            .collect()
+    }
+}
+
+/// A successfully executed transaction
+pub struct TxOutcome {
+    pub digest: String,
+    pub events: Vec<SuiEvent>,
+    pub gas_budget: u64,
+    pub gas: GasCost,
+    /// Versions of the mutated objects before the transaction
+    pub modified_at_versions: Vec<(ObjectID, SequenceNumber)>,
+}
+
+impl TxOutcome {
+    /// First event of the given struct name (e.g. `MarketCreated`), decoded
+    pub fn event<T: DeserializeOwned>(&self, name: &str) -> Option<T> {
+        self.events
+            .iter()
+            .find(|e| e.type_.name.as_str() == name)
+            .and_then(|e| serde_json::from_value(e.parsed_json.clone()).ok())
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DryRunParams {
+    /// Simulate the transaction without signing or submitting it
+    pub dry_run: Option<bool>,
+}
+
+impl DryRunParams {
+    pub fn enabled(&self) -> bool {
+        self.dry_run.unwrap_or(false)
+    }
+}
+
+/// Gas charged by a transaction, in MIST
+#[derive(Serialize, ToSchema)]
+pub struct GasCost {
+    pub computation_cost: u64,
+    pub storage_cost: u64,
+    pub storage_rebate: u64,
+    /// computation + storage - rebate
+    pub net: i64,
+}
+
+impl From<&GasCostSummary> for GasCost {
+    fn from(summary: &GasCostSummary) -> Self {
+        GasCost {
+            computation_cost: summary.computation_cost,
+            storage_cost: summary.storage_cost,
+            storage_rebate: summary.storage_rebate,
+            net: summary.net_gas_usage(),
+        }
+    }
+}
+
+/// Outcome of simulating a transaction with `dry_run=true`
+#[derive(Serialize, ToSchema)]
+pub struct DryRunReport {
+    pub success: bool,
+    /// Execution error, e.g. a Move abort, when `success` is false
+    pub error: Option<String>,
+    /// Error code of a `market` abort, e.g. `market_already_resolved`
+    pub error_code: Option<&'static str>,
+    pub gas: GasCost,
+    /// Budget a real submission would set; absent when the estimate exceeds `GAS_BUDGET_MAX`
+    pub gas_budget: Option<u64>,
+    #[schema(value_type = Vec<Object>)]
+    pub events: Vec<serde_json::Value>,
+    /// Full transaction effects as returned by the fullnode
+    #[schema(value_type = Object)]
+    pub effects: serde_json::Value,
+}
+
+/// Gas budget policy: dry-run estimate plus a margin, never above the cap
+pub struct GasBudgetConfig {
+    pub margin_percent: u64,
+    pub max: u64,
+}
+
+impl GasBudgetConfig {
+    pub fn from_env() -> Self {
+        fn env_u64(key: &str, default: u64) -> u64 {
+            std::env::var(key)
+                .ok()
+                .and_then(|v| v.trim().parse().ok())
+                .unwrap_or(default)
+        }
+        GasBudgetConfig {
+            margin_percent: env_u64("GAS_BUDGET_MARGIN_PERCENT", 20),
+            max: env_u64("GAS_BUDGET_MAX", 50_000_000),
+        }
+    }
+
+    /// Budget for a transaction whose dry run cost `summary`.
+    /// The rebate is only credited after execution, so it does not lower the budget.
+    pub fn budget_for(&self, summary: &GasCostSummary) -> Result<u64, TxError> {
+        let estimate = summary
+            .computation_cost
+            .saturating_add(summary.storage_cost);
+        let budget = estimate.saturating_mul(100 + self.margin_percent) / 100;
+        if budget > self.max {
+            return Err(TxError::Build(format!(
+                "Estimated gas budget {} MIST exceeds GAS_BUDGET_MAX {}",
+                budget, self.max
+            )));
+        }
+        Ok(budget)
+    }
+}
+
+pub struct Executor {
+    client: SuiClient,
+    signer: Signer,
+    package_id: ObjectID,
+    budget: GasBudgetConfig,
+}
+
+impl Executor {
+    pub async fn from_env() -> Result<Executor, TxError> {
+        let package_id = chain::package_id().map_err(TxError::Config)?;
+        let signer = Signer::from_env().map_err(TxError::Config)?;
+        let client = connect().await?;
+
+        Ok(Executor {
+            client,
+            signer,
+            package_id,
+            budget: GasBudgetConfig::from_env(),
+        })
+    }
+
+    pub fn sender(&self) -> SuiAddress {
+        self.signer.address()
+    }
+
+    pub fn client(&self) -> &SuiClient {
+        &self.client
+    }
+
+    /// Upper bound used while building, before the estimate is known
+    pub fn gas_budget(&self) -> u64 {
+        self.budget.max
+    }
+
+    pub async fn gas_price(&self) -> Result<u64, TxError> {
+        self.client
+            .read_api()
+            .get_reference_gas_price()
+            .await
+            .map_err(|e| TxError::Submit(format!("Failed to fetch gas price: {}", e)))
+    }
+
+    /// Build `call` and simulate it; nothing is signed or submitted
+    pub async fn dry_run(&self, call: MoveCall) -> Result<DryRunReport, TxError> {
+        let (tx_data, _lease) = self.build(call).await?;
+        let response = simulate(&self.client, tx_data).await?;
+
+        let effects = &response.effects;
+        let error = match effects.status() {
+            SuiExecutionStatus::Success => None,
+            SuiExecutionStatus::Failure { error } => Some(error.clone()),
+        };
+        let summary = effects.gas_cost_summary();
+
+        Ok(DryRunReport {
+            success: error.is_none(),
+            error_code: error
+                .as_deref()
+                .and_then(MarketAbort::parse)
+                .map(MarketAbort::name),
+            error,
+            gas: GasCost::from(summary),
+            gas_budget: self.budget.budget_for(summary).ok(),
+            events: response
+                .events
+                .data
+                .iter()
+                .map(|e| serde_json::json!({ "type": e.type_.to_string(), "data": e.parsed_json }))
+                .collect(),
+            effects: serde_json::to_value(&response.effects).unwrap_or_default(),
+        })
+    }
+
+    /// Build at the budget cap; `sign` lowers it to the estimate
+    pub async fn build(&self, call: MoveCall) -> Result<(TransactionData, GasLease), TxError> {
+        let sender = self.sender();
+        let gas_price = self.gas_price().await?;
+        let (gas_ref, lease) = gas::reserve_gas(&self.client, sender, self.budget.max).await?;
+
+        let tx_data = self
+            .client
+            .transaction_builder()
+            .move_call(
+                sender,
+                self.package_id,
+                MARKET_MODULE,
+                call.function,
+                vec![],
+                call.args,
+                Some(gas_ref.0),
+                self.budget.max,
+                Some(gas_price),
+            )
+            .await
+            .map_err(|e| TxError::Build(format!("Failed to build {}: {}", call.function, e)))?;
+
+        Ok((tx_data, lease))
+    }
+
+    /// Build several calls into one programmable transaction; a single call is built as by `build`
+    pub async fn build_batch(
+        &self,
+        mut calls: Vec<MoveCall>,
+    ) -> Result<(TransactionData, GasLease), TxError> {
+        if calls.len() == 1 {
+            return self.build(calls.remove(0)).await;
+        }
+
+        let sender = self.sender();
+        let (gas_ref, lease) = gas::reserve_gas(&self.client, sender, self.budget.max).await?;
+
+        let params = calls
+            .into_iter()
+            .map(|call| {
+                RPCTransactionRequestParams::MoveCallRequestParams(MoveCallParams {
+                    package_object_id: self.package_id,
+                    module: MARKET_MODULE.to_string(),
+                    function: call.function.to_string(),
+                    type_arguments: vec![],
+                    arguments: call.args,
+                })
+            })
+            .collect();
+
+        let tx_data = self
+            .client
+            .transaction_builder()
+            .batch_transaction(sender, params, Some(gas_ref.0), self.budget.max)
+            .await
+            .map_err(|e| TxError::Build(format!("Failed to build batch: {}", e)))?;
+
+        Ok((tx_data, lease))
+    }
+
+    /// Pay gas for a transaction built by `sender`: attach a pool coin, set the
+    /// budget from a dry run (capped by `budget`) and sign as gas owner.
+    /// The sender signs and submits the result; the coin is leased to it.
+    pub async fn sponsor(
+        &self,
+        kind: TransactionKind,
+        sender: SuiAddress,
+        budget: &GasBudgetConfig,
+    ) -> Result<(TransactionData, Signature, GasLease), TxError> {
+        let gas_price = self.gas_price().await?;
+        let (gas_ref, lease) = gas::reserve_gas(&self.client, self.sender(), budget.max).await?;
+
+        let mut tx_data = TransactionData::new_with_gas_data(
+            kind,
+            sender,
+            GasData {
+                payment: vec![gas_ref],
+                owner: self.sender(),
+                price: gas_price,
+                budget: budget.max,
+            },
+        );
+        set_budget(&self.client, budget, &mut tx_data, "sponsored").await?;
+
+        let signature = self.signer.sign(&tx_data).await.map_err(TxError::Config)?;
+        Ok((tx_data, signature, lease))
+    }
+
+    /// Estimate the budget, then sign, execute and check an already built transaction
+    pub async fn submit(
+        &self,
+        tx_data: TransactionData,
+        label: &str,
+    ) -> Result<TxOutcome, TxError> {
+        let signed = self.sign(tx_data, label).await?;
+        self.send(&signed).await
+    }
+
+    /// Set the budget from a dry-run estimate and sign; the digest is known from here on
+    pub async fn sign(
+        &self,
+        mut tx_data: TransactionData,
+        label: &str,
+    ) -> Result<SignedTx, TxError> {
+        let gas_budget = set_budget(&self.client, &self.budget, &mut tx_data, label).await?;
+
+        let signature = self.signer.sign(&tx_data).await.map_err(TxError::Config)?;
+        let transaction = Transaction::from_data(tx_data, vec![signature]);
+
+        Ok(SignedTx {
+            digest: transaction.digest().to_string(),
+            transaction,
+            gas_budget,
+            label: label.to_string(),
+        })
+    }
+
+    /// Execute a signed transaction. Resending the same transaction is safe:
+    /// the network executes a digest at most once.
+    pub async fn send(&self, signed: &SignedTx) -> Result<TxOutcome, TxError> {
+        let outcome = execute(&self.client, signed).await?;
+        println!(
+            "Tx: {} {} executed by {} (gas {} of budget {})",
+            signed.label,
+            outcome.digest,
+            self.sender(),
+            outcome.gas.net,
+            outcome.gas_budget
+        );
+        Ok(outcome)
+    }
+
+    /// Outcome of an earlier submission, or `None` if the fullnode has not seen it
+    pub async fn lookup(
+        &self,
+        digest: &str,
+        gas_budget: u64,
+    ) -> Result<Option<TxOutcome>, TxError> {
+        let digest = TransactionDigest::from_str(digest)
+            .map_err(|e| TxError::Build(format!("Invalid digest {}: {}", digest, e)))?;
+        match self
+            .client
+            .read_api()
+            .get_transaction_with_options(
+                digest,
+                SuiTransactionBlockResponseOptions::new()
+                    .with_effects()
+                    .with_events(),
+            )
+            .await
+        {
+            Ok(response) => outcome(response, gas_budget).map(Some),
+            // Not found and transport errors look alike; resending is safe either way
+            Err(_) => Ok(None),
+        }
+    }
+}
+
+pub async fn connect() -> Result<SuiClient, TxError> {
+    SuiClientBuilder::default()
+        .build(chain::sui_network())
+        .await
+        .map_err(|e| TxError::Submit(format!("Failed to create Sui client: {}", e)))
+}
+
+async fn simulate(
+    client: &SuiClient,
+    tx_data: TransactionData,
+) -> Result<DryRunTransactionBlockResponse, TxError> {
+    client
+        .read_api()
+        .dry_run_transaction_block(tx_data)
+        .await
+        .map_err(|e| TxError::Submit(format!("Dry run failed: {}", e)))
+}
+
+/// Dry-run `tx_data` and lower its budget to the estimate; errors if it would abort
+async fn set_budget(
+    client: &SuiClient,
+    budget: &GasBudgetConfig,
+    tx_data: &mut TransactionData,
+    label: &str,
+) -> Result<u64, TxError> {
+    let estimate = simulate(client, tx_data.clone()).await?;
+    if let SuiExecutionStatus::Failure { error } = estimate.effects.status() {
+        return Err(TxError::execution(error.clone(), None, label));
+    }
+    let gas_budget = budget.budget_for(estimate.effects.gas_cost_summary())?;
+    tx_data.gas_data_mut().budget = gas_budget;
+    Ok(gas_budget)
+}
+
+/// Build `call` for a sender outside this backend, e.g. a multisig oracle.
+/// The sender pays gas from its own coins.
+pub async fn build_unsigned(
+    client: &SuiClient,
+    budget: &GasBudgetConfig,
+    sender: SuiAddress,
+    call: MoveCall,
+) -> Result<(TransactionData, u64), TxError> {
+    let package_id = chain::package_id().map_err(TxError::Config)?;
+    let function = call.function;
+    let mut tx_data = client
+        .transaction_builder()
+        .move_call(
+            sender,
+            package_id,
+            MARKET_MODULE,
+            function,
+            vec![],
+            call.args,
+            None,
+            budget.max,
+            None,
+        )
+        .await
+        .map_err(|e| TxError::Build(format!("Failed to build {}: {}", function, e)))?;
+    let gas_budget = set_budget(client, budget, &mut tx_data, function).await?;
+    Ok((tx_data, gas_budget))
+}
+
+/// Execute a signed transaction and check its effects. Resending the same
+/// transaction is safe: the network executes a digest at most once.
+pub async fn execute(client: &SuiClient, signed: &SignedTx) -> Result<TxOutcome, TxError> {
+    let response = client
+        .quorum_driver_api()
+        .execute_transaction_block(
+            signed.transaction.clone(),
+            SuiTransactionBlockResponseOptions::new()
+                .with_effects()
+                .with_events(),
+            Some(ExecuteTransactionRequestType::WaitForLocalExecution),
+        )
+        .await
+        .map_err(|e| TxError::Submit(format!("Failed to execute transaction: {}", e)))?;
+
+    outcome(response, signed.gas_budget)
+}
+
+fn outcome(response: SuiTransactionBlockResponse, gas_budget: u64) -> Result<TxOutcome, TxError> {
+    let digest = response.digest.to_string();
+    let effects = response
+        .effects
+        .ok_or_else(|| TxError::Submit(format!("Transaction {} returned no effects", digest)))?;
+    if let SuiExecutionStatus::Failure { error } = effects.status() {
+        return Err(TxError::execution(
+            error.clone(),
+            Some(digest),
+            "transaction",
+        ));
+    }
+
+    Ok(TxOutcome {
+        digest,
+        events: response.events.map(|e| e.data).unwrap_or_default(),
+        gas_budget,
+        gas: GasCost::from(effects.gas_cost_summary()),
+        modified_at_versions: effects.modified_at_versions(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(computation: u64, storage: u64, rebate: u64) -> GasCostSummary {
+        GasCostSummary::new(computation, storage, rebate, 0)
+    }
+
+    #[test]
+    fn budget_adds_margin_to_computation_and_storage() {
+        let config = GasBudgetConfig {
+            margin_percent: 20,
+            max: 50_000_000,
+        };
+        assert_eq!(
+            config
+                .budget_for(&summary(1_000_000, 2_000_000, 0))
+                .unwrap(),
+            3_600_000
+        );
+    }
+
+    #[test]
+    fn rebate_does_not_lower_budget() {
+        let config = GasBudgetConfig {
+            margin_percent: 0,
+            max: 50_000_000,
+        };
+        assert_eq!(
+            config
+                .budget_for(&summary(1_000_000, 2_000_000, 2_500_000))
+                .unwrap(),
+            3_000_000
+        );
+    }
+
+    #[test]
+    fn budget_over_cap_is_rejected() {
+        let config = GasBudgetConfig {
+            margin_percent: 20,
+            max: 3_000_000,
+        };
+        assert!(matches!(
+            config.budget_for(&summary(1_000_000, 2_000_000, 0)),
+            Err(TxError::Build(_))
+        ));
+        // Exactly at the cap is allowed
+        assert_eq!(
+            config
+                .budget_for(&summary(1_000_000, 1_500_000, 0))
+                .unwrap(),
+            3_000_000
+        );
+    }
+
+    #[test]
+    fn huge_estimates_saturate_instead_of_overflowing() {
+        let config = GasBudgetConfig {
+            margin_percent: 20,
+            max: u64::MAX,
+        };
+        assert!(config.budget_for(&summary(u64::MAX, u64::MAX, 0)).is_ok());
+    }
+}
@@ -0,0 +1,113 @@
+//! Request validation with field-level error responses.
+
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+/// 400 response body listing every invalid field
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ValidationErrors {
+    pub error: &'static str,
+    pub fields: Vec<FieldError>,
+}
+
+impl ValidationErrors {
+    pub fn new() -> Self {
+        ValidationErrors {
+            error: "validation_failed",
+            fields: Vec::new(),
+        }
+    }
+
+    pub fn add(&mut self, field: impl Into<String>, message: impl Into<String>) {
+        self.fields.push(FieldError {
+            field: field.into(),
+            message: message.into(),
+        });
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    /// `Ok(value)` when no field failed, otherwise the collected errors
+    pub fn into_result<T>(self, value: T) -> Result<T, ValidationErrors> {
+        if self.is_empty() {
+            Ok(value)
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl Default for ValidationErrors {
+    fn default() -> Self {
+        ValidationErrors::new()
+    }
+}
+
+impl IntoResponse for ValidationErrors {
+    fn into_response(self) -> Response {
+        (StatusCode::BAD_REQUEST, Json(self)).into_response()
+    }
+}
+
+/// Parse a market end date: RFC 3339, or a bare `YYYY-MM-DD` meaning end of that day (UTC)
+pub fn parse_end_date(date_str: &str) -> Option<chrono::DateTime<chrono::FixedOffset>> {
+    chrono::DateTime::parse_from_rfc3339(date_str)
+        .ok()
+        .or_else(|| {
+            chrono::NaiveDate::parse_from_str(date_str, "%Y-%m-%d")
+                .ok()
+                .and_then(|d| d.and_hms_opt(23, 59, 59))
+                .map(|dt| dt.and_utc().fixed_offset())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Datelike, Timelike};
+
+    #[test]
+    fn default_has_error_code() {
+        assert_eq!(ValidationErrors::default().error, "validation_failed");
+    }
+
+    #[test]
+    fn parses_rfc3339_with_offset() {
+        let date = parse_end_date("2025-06-01T12:30:00+02:00").unwrap();
+        assert_eq!(date.to_rfc3339(), "2025-06-01T12:30:00+02:00");
+    }
+
+    #[test]
+    fn bare_date_means_end_of_day_utc() {
+        let date = parse_end_date("2025-06-01").unwrap();
+        assert_eq!((date.year(), date.month(), date.day()), (2025, 6, 1));
+        assert_eq!((date.hour(), date.minute(), date.second()), (23, 59, 59));
+        assert_eq!(date.offset().local_minus_utc(), 0);
+    }
+
+    #[test]
+    fn rejects_other_formats() {
+        for input in [
+            "",
+            "2025/06/01",
+            "2025-13-01",
+            "2025-06-01 12:00:00",
+            "tomorrow",
+        ] {
+            assert!(parse_end_date(input).is_none(), "{:?}", input);
+        }
+    }
+}
@@ -1,4 +1,4 @@
-import { useCurrentAccount, useSignAndExecuteTransaction, ConnectButton } from '@mysten/dapp-kit';
+import { useCurrentAccount, useSignAndExecuteTransaction, useSignPersonalMessage, ConnectButton } from '@mysten/dapp-kit';
 import { Transaction } from '@mysten/sui/transactions';
 import { useState, useEffect } from 'react';
 import { Trophy, TrendingUp, AlertCircle, CheckCircle2, Coins, ArrowLeft, Loader2, Star } from 'lucide-react';
@@ -6,6 +6,7 @@ import MarketChart from './components/MarketChart';
 import { Navbar } from './components/Navbar';
 import { MarketCard } from './components/MarketCard';
 import { DebugTools } from './components/DebugTools';
+import { getSessionToken } from './session';
 import { ModalProvider, useModal } from './context/ModalContext'; // Import useModal
 import clsx from 'clsx';
 
@@ -48,6 +49,7 @@ function App() {
 function AppContent() {
   const account = useCurrentAccount();
   const { mutate: signAndExecute } = useSignAndExecuteTransaction();
+  const { mutateAsync: signPersonalMessage } = useSignPersonalMessage();
   const { alert } = useModal(); // Use custom alert
 
   // Navigation State
@@ -136,6 +138,14 @@ function AppContent() {
     }
   }, [selectedContract, view, timeRange, refetchVersion]);
 
+  // Live updates: refetch when the backend indexer pushes a market event
+  useEffect(() => {
+    const source = new EventSource(`${API_BASE}/stream`);
+    const bump = () => setRefetchVersion(v => v + 1);
+    ['history', 'bet', 'resolved', 'cancelled'].forEach(name => source.addEventListener(name, bump));
+    return () => source.close();
+  }, []);
+
 
 
   const handleContractClick = (contract: Contract, initialOutcomeIndex: number = 0) => {
@@ -153,13 +163,23 @@ function AppContent() {
       return;
     }
 
+    // Favorites are tied to a signed session proving control of the wallet
+    let token: string;
+    try {
+      token = await getSessionToken(account.address, signPersonalMessage);
+    } catch (e) {
+      console.error(e);
+      return;
+    }
+    const headers = { 'Content-Type': 'application/json', 'Authorization': `Bearer ${token}` };
+
     const isFav = favorites.includes(contractId);
     if (isFav) {
       // Remove
       try {
         const res = await fetch(`${API_BASE}/favorites`, {
           method: 'DELETE',
-          headers: { 'Content-Type': 'application/json' },
+          headers,
           body: JSON.stringify({ wallet_address: account.address, contract_id: contractId })
         });
         if (res.ok) {
@@ -171,7 +191,7 @@ function AppContent() {
       try {
         const res = await fetch(`${API_BASE}/favorites`, {
           method: 'POST',
-          headers: { 'Content-Type': 'application/json' },
+          headers,
           body: JSON.stringify({ wallet_address: account.address, contract_id: contractId })
         });
         if (res.ok) {
@@ -1,9 +1,11 @@
-import { useState } from 'react';
+import { useRef, useState } from 'react';
+import { useCurrentAccount, useSignPersonalMessage } from '@mysten/dapp-kit';
 import { Plus, Trash2, Wrench, Calendar, X } from 'lucide-react';
 import clsx from 'clsx';
 import DatePicker from 'react-datepicker';
 import 'react-datepicker/dist/react-datepicker.css';
 import { useModal } from '../context/ModalContext'; // Import useModal
+import { getSessionToken } from '../session';
 
 interface Category {
     id: number;
@@ -27,6 +29,8 @@ interface DebugToolsProps {
 
 export function DebugTools({ categories, contracts, onMarketCreated, onMarketResolved }: DebugToolsProps) {
     const { alert, confirm } = useModal(); // Use custom hooks
+    const account = useCurrentAccount();
+    const { mutateAsync: signPersonalMessage } = useSignPersonalMessage();
     const [isOpen, setIsOpen] = useState(false);
     const [isCreating, setIsCreating] = useState(false);
     const [activeTab, setActiveTab] = useState<'create' | 'oracle'>('create');
@@ -37,6 +41,8 @@ export function DebugTools({ categories, contracts, onMarketCreated, onMarketRes
     const [newContractOptions, setNewContractOptions] = useState<string[]>([]);
     const [selectedCategoryId, setSelectedCategoryId] = useState<number | null>(null);
     const [newContractEndDate, setNewContractEndDate] = useState<Date | null>(null);
+    // Reused across retries until a create succeeds, so a timed-out request is never doubled
+    const createKey = useRef<string | null>(null);
 
     // Oracle Tools State
     const [selectedMarketId, setSelectedMarketId] = useState<number | null>(null);
@@ -66,6 +72,13 @@ export function DebugTools({ categories, contracts, onMarketCreated, onMarketRes
 
     const API_BASE = import.meta.env.DEV ? 'http://localhost:3000' : '';
 
+    // Admin endpoints require a session signed by a wallet listed in ADMIN_ADDRESSES
+    const adminHeaders = async () => {
+        if (!account) throw new Error("Connect an admin wallet first");
+        const token = await getSessionToken(account.address, signPersonalMessage);
+        return { 'Content-Type': 'application/json', 'Authorization': `Bearer ${token}` };
+    };
+
     const handleResolveMarket = async () => {
         if (!selectedMarket) {
             await alert("Please select a market to resolve.");
@@ -82,7 +95,7 @@ export function DebugTools({ categories, contracts, onMarketCreated, onMarketRes
         try {
             const res = await fetch(`${API_BASE}/oracle/resolve`, {
                 method: 'POST',
-                headers: { 'Content-Type': 'application/json' },
+                headers: await adminHeaders(),
                 body: JSON.stringify({
                     market_id: selectedMarket.address,
                     winner: selectedWinnerIndex,
@@ -90,12 +103,23 @@ export function DebugTools({ categories, contracts, onMarketCreated, onMarketRes
             });
 
             if (!res.ok) {
+                // Transaction errors come back as { error, message, abort_code, digest }
                 const errText = await res.text();
-                throw new Error(errText || "Failed to resolve market");
+                let message = errText;
+                try {
+                    message = JSON.parse(errText).message ?? errText;
+                } catch {
+                    // Plain-text error
+                }
+                throw new Error(message || "Failed to resolve market");
             }
 
             const result = await res.json();
-            await alert(`Market resolved successfully!\n\nTransaction: ${result.digest}\nStatus: ${result.status}`);
+            if (res.status === 202) {
+                await alert(`Resolution submitted, awaiting confirmation.\n\nTransaction: ${result.digest}\nJob: ${result.id}`);
+            } else {
+                await alert(`Market resolved successfully!\n\nTransaction: ${result.digest}\nStatus: ${result.status}`);
+            }
 
             // Reset state
             setSelectedMarketId(null);
@@ -114,9 +138,11 @@ export function DebugTools({ categories, contracts, onMarketCreated, onMarketRes
     };
 
     const createMarketBackend = async (name: string, desc: string, options: string[], categoryId: number | null, endDate: string) => {
+        const key = createKey.current ?? crypto.randomUUID();
+        createKey.current = key;
         const res = await fetch(`${API_BASE}/contracts`, {
             method: 'POST',
-            headers: { 'Content-Type': 'application/json' },
+            headers: { ...(await adminHeaders()), 'Idempotency-Key': key },
             body: JSON.stringify({
                 name: name,
                 address: "", // Empty address signals Backend to create on-chain
@@ -126,8 +152,20 @@ export function DebugTools({ categories, contracts, onMarketCreated, onMarketRes
                 end_date: endDate || null,
             })
         });
-        if (!res.ok) throw new Error("Backend failed to create market");
-        return await res.json();
+        if (!res.ok) {
+            const body = await res.text();
+            try {
+                // Field-level validation errors: { error, fields: [{ field, message }] }
+                const parsed = JSON.parse(body);
+                if (parsed.fields) {
+                    throw new Error(parsed.fields.map((f: any) => `${f.field}: ${f.message}`).join("\n"));
+                }
+            } catch (e) {
+                if (!(e instanceof SyntaxError)) throw e;
+            }
+            throw new Error(body || "Backend failed to create market");
+        }
+        return { pending: res.status === 202, body: await res.json() };
     };
 
     const handleCreateMarket = async () => {
@@ -150,8 +188,8 @@ export function DebugTools({ categories, contracts, onMarketCreated, onMarketRes
         setIsCreating(true);
 
         try {
-            const newContract = await createMarketBackend(newContractName, newContractDesc, optionsArray, selectedCategoryId, newContractEndDate ? newContractEndDate.toISOString() : "");
-            onMarketCreated(newContract);
+            const { pending, body } = await createMarketBackend(newContractName, newContractDesc, optionsArray, selectedCategoryId, newContractEndDate ? newContractEndDate.toISOString() : "");
+            createKey.current = null;
 
             // Reset Form
             setNewContractName("");
@@ -160,11 +198,18 @@ export function DebugTools({ categories, contracts, onMarketCreated, onMarketRes
             setSelectedCategoryId(null);
             setNewContractEndDate(null);
 
+            if (pending) {
+                await alert(`Market creation submitted, awaiting confirmation.\n\nTransaction: ${body.digest}\nJob: ${body.id}`);
+                setIsOpen(false);
+                return;
+            }
+            onMarketCreated(body);
+
             await alert("Market created successfully (by Admin Backend)!");
             setIsOpen(false); // Close modal on success
-        } catch (e) {
+        } catch (e: any) {
             console.error(e);
-            await alert("Failed to create market. See console.");
+            await alert(`Failed to create market:\n${e.message}`);
         } finally {
             setIsCreating(false);
         }
@@ -0,0 +1,48 @@
+// Sign-In-with-Sui session tokens issued by the backend (/auth/challenge + /auth/login).
+// Tokens are cached per address in localStorage until they expire.
+
+const API_BASE = import.meta.env.DEV ? 'http://localhost:3000' : '';
+
+type SignPersonalMessage = (args: { message: Uint8Array }) => Promise<{ signature: string }>;
+
+interface StoredSession {
+    token: string;
+    expires_at: string;
+}
+
+const storageKey = (address: string) => `session:${address}`;
+
+export function clearSession(address: string) {
+    localStorage.removeItem(storageKey(address));
+}
+
+export async function getSessionToken(address: string, signPersonalMessage: SignPersonalMessage): Promise<string> {
+    const cached = localStorage.getItem(storageKey(address));
+    if (cached) {
+        const session: StoredSession = JSON.parse(cached);
+        if (new Date(session.expires_at).getTime() > Date.now() + 60_000) {
+            return session.token;
+        }
+    }
+
+    const challengeRes = await fetch(`${API_BASE}/auth/challenge`, {
+        method: 'POST',
+        headers: { 'Content-Type': 'application/json' },
+        body: JSON.stringify({ address }),
+    });
+    if (!challengeRes.ok) throw new Error(await challengeRes.text());
+    const challenge = await challengeRes.json();
+
+    const { signature } = await signPersonalMessage({ message: new TextEncoder().encode(challenge.message) });
+
+    const loginRes = await fetch(`${API_BASE}/auth/login`, {
+        method: 'POST',
+        headers: { 'Content-Type': 'application/json' },
+        body: JSON.stringify({ address, nonce: challenge.nonce, signature }),
+    });
+    if (!loginRes.ok) throw new Error(await loginRes.text());
+    const session = await loginRes.json();
+
+    localStorage.setItem(storageKey(address), JSON.stringify({ token: session.token, expires_at: session.expires_at }));
+    return session.token;
+}